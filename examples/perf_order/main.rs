@@ -64,7 +64,7 @@ fn run_perf_test(duration_secs: u64, num_to_try: usize) -> bool {
             break;
         }
 
-        book.insert_order(order);
+        _ = book.insert_order(order);
         order_count += 1;
     }
 
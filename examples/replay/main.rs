@@ -0,0 +1,72 @@
+use std::{env, fs, process};
+
+use market_forge::replay::{diff_lines, journal_steps, parse_steps, replay};
+
+const USAGE: &str = "usage: replay <command file> [--journal] [--golden <file> [--bless]]
+
+Replay the commands into a fresh order book and print the events of every command.
+  --journal         read the command file as a binary journal instead of text
+  --golden <file>   compare the output with the golden file, exit with 1 on any difference
+  --bless           write the output to the golden file instead of comparing";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut command_file = None;
+    let mut golden_file = None;
+    let mut is_journal = false;
+    let mut is_bless = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--journal" => is_journal = true,
+            "--bless" => is_bless = true,
+            "--golden" => golden_file = args.next(),
+            "-h" | "--help" => exit(USAGE),
+            _ if command_file.is_none() => command_file = Some(arg),
+            _ => exit(USAGE),
+        }
+    }
+
+    let Some(command_file) = command_file else {
+        exit(USAGE);
+    };
+
+    let steps = if is_journal {
+        fs::read(command_file)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| journal_steps(&bytes).map_err(|err| err.to_string()))
+    } else {
+        fs::read_to_string(command_file)
+            .map_err(|err| err.to_string())
+            .and_then(|text| parse_steps(&text).map_err(|err| err.to_string()))
+    };
+    let steps = steps.unwrap_or_else(|err| exit(&format!("{}: {}", command_file, err)));
+    let output = replay(&steps);
+
+    let Some(golden_file) = golden_file else {
+        print!("{}", output);
+        return;
+    };
+
+    if is_bless {
+        if let Err(err) = fs::write(golden_file, &output) {
+            exit(&format!("{}: {}", golden_file, err));
+        }
+        println!("{} blessed", golden_file);
+        return;
+    }
+
+    let golden = fs::read_to_string(golden_file)
+        .unwrap_or_else(|err| exit(&format!("{}: {}", golden_file, err)));
+    if let Some(diff) = diff_lines(&golden, &output) {
+        exit(&format!("{} differs at {}", golden_file, diff));
+    }
+
+    println!("{} matches", golden_file);
+}
+
+fn exit(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
@@ -0,0 +1,71 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::core::order::Timestamp;
+
+/// Source of time of the book, nanoseconds since the unix epoch for the system clock
+pub trait Clock {
+    fn now(&self) -> Timestamp;
+}
+
+// Wall clock time at creation moved forward by a monotonic clock, never goes backward
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    origin: Instant,
+    origin_time: Timestamp,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        let origin_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as Timestamp);
+
+        Self {
+            origin: Instant::now(),
+            origin_time,
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    #[inline(always)]
+    fn now(&self) -> Timestamp {
+        self.origin_time + self.origin.elapsed().as_nanos() as Timestamp
+    }
+}
+
+// Time only moves when told to, for tests and replays
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ManualClock {
+    now: Timestamp,
+}
+
+impl ManualClock {
+    #[inline(always)]
+    pub fn new(now: Timestamp) -> Self {
+        Self { now }
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, now: Timestamp) {
+        self.now = now;
+    }
+
+    #[inline(always)]
+    pub fn advance(&mut self, duration: Timestamp) {
+        self.now += duration;
+    }
+}
+
+impl Clock for ManualClock {
+    #[inline(always)]
+    fn now(&self) -> Timestamp {
+        self.now
+    }
+}
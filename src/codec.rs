@@ -0,0 +1,405 @@
+use thiserror::Error;
+
+use crate::core::{
+    order::{
+        ExecutionCondition, MarketProtection, OrderSide, OrderType, PostOnly, SelfTradePrevention,
+        TimeInForce, TrailingOffset,
+    },
+    order_link::OcoMode,
+    order_spec::OrderSpec,
+};
+
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum CodecError {
+    #[error("Unexpected end of data")]
+    UnexpectedEnd,
+
+    #[error("Invalid {field} value {value}")]
+    InvalidValue { field: &'static str, value: u64 },
+}
+
+/// Little endian, fixed width encoding of the persisted data
+#[derive(Debug, Clone, Default)]
+pub struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline(always)]
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    #[inline(always)]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+
+    #[inline(always)]
+    pub fn put_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    #[inline(always)]
+    pub fn put_u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    #[inline(always)]
+    pub fn put_u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    #[inline(always)]
+    pub fn put_u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    #[inline(always)]
+    pub fn put_i64(&mut self, value: i64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    #[inline(always)]
+    pub fn put_u128(&mut self, value: u128) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    // Overwrite bytes already written, used to fill in lengths and checksums
+    #[inline(always)]
+    pub fn patch_u32(&mut self, position: usize, value: u32) {
+        self.bytes[position..position + 4].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// Reads back what the `Encoder` wrote
+#[derive(Debug, Clone)]
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    #[inline(always)]
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    #[inline(always)]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], CodecError> {
+        if self.remaining() < len {
+            return Err(CodecError::UnexpectedEnd);
+        }
+
+        let bytes = &self.bytes[self.position..self.position + len];
+        self.position += len;
+
+        Ok(bytes)
+    }
+
+    #[inline(always)]
+    pub fn get_u8(&mut self) -> Result<u8, CodecError> {
+        Ok(self.take(1)?[0])
+    }
+
+    #[inline(always)]
+    pub fn get_u16(&mut self) -> Result<u16, CodecError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    #[inline(always)]
+    pub fn get_u32(&mut self) -> Result<u32, CodecError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    #[inline(always)]
+    pub fn get_u64(&mut self) -> Result<u64, CodecError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    #[inline(always)]
+    pub fn get_i64(&mut self) -> Result<i64, CodecError> {
+        Ok(i64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    #[inline(always)]
+    pub fn get_u128(&mut self) -> Result<u128, CodecError> {
+        Ok(u128::from_le_bytes(self.take(16)?.try_into().unwrap()))
+    }
+}
+
+/// Binary form of an order, implemented by orders that are journaled or snapshotted
+pub trait OrderCodec: Sized {
+    fn encode(&self, encoder: &mut Encoder);
+    fn decode(decoder: &mut Decoder) -> Result<Self, CodecError>;
+}
+
+impl OrderCodec for OrderSpec {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.put_u32(self.id);
+        encoder.put_u32(self.owner_id);
+        encoder.put_u64(self.price);
+        encoder.put_u64(self.stop_price);
+        encode_trailing_offset(encoder, self.trailing_offset);
+        encoder.put_u64(self.quantity);
+        encoder.put_u8(encode_order_side(self.order_side));
+        encoder.put_u8(encode_order_type(self.order_type));
+        encoder.put_u8(encode_time_in_force(self.time_in_force));
+        encoder.put_u8(encode_execution_condition(self.execution_condition));
+        encoder.put_u8(encode_post_only(self.post_only));
+        encoder.put_u8(encode_self_trade_prevention(self.self_trade_prevention));
+        encode_market_protection(encoder, self.market_protection);
+        encoder.put_u64(self.expire_time);
+        encoder.put_u64(self.accept_time);
+    }
+
+    fn decode(decoder: &mut Decoder) -> Result<Self, CodecError> {
+        Ok(OrderSpec {
+            id: decoder.get_u32()?,
+            owner_id: decoder.get_u32()?,
+            price: decoder.get_u64()?,
+            stop_price: decoder.get_u64()?,
+            trailing_offset: decode_trailing_offset(decoder)?,
+            quantity: decoder.get_u64()?,
+            order_side: decode_order_side(decoder.get_u8()?)?,
+            order_type: decode_order_type(decoder.get_u8()?)?,
+            time_in_force: decode_time_in_force(decoder.get_u8()?)?,
+            execution_condition: decode_execution_condition(decoder.get_u8()?)?,
+            post_only: decode_post_only(decoder.get_u8()?)?,
+            self_trade_prevention: decode_self_trade_prevention(decoder.get_u8()?)?,
+            market_protection: decode_market_protection(decoder)?,
+            expire_time: decoder.get_u64()?,
+            accept_time: decoder.get_u64()?,
+        })
+    }
+}
+
+#[inline(always)]
+fn invalid<T>(field: &'static str, value: u8) -> Result<T, CodecError> {
+    Err(CodecError::InvalidValue {
+        field,
+        value: value as u64,
+    })
+}
+
+#[inline(always)]
+pub fn encode_order_side(order_side: OrderSide) -> u8 {
+    match order_side {
+        OrderSide::Buy => 0,
+        OrderSide::Sell => 1,
+    }
+}
+
+#[inline(always)]
+pub fn decode_order_side(value: u8) -> Result<OrderSide, CodecError> {
+    match value {
+        0 => Ok(OrderSide::Buy),
+        1 => Ok(OrderSide::Sell),
+        _ => invalid("order side", value),
+    }
+}
+
+#[inline(always)]
+fn encode_order_type(order_type: OrderType) -> u8 {
+    match order_type {
+        OrderType::Market => 0,
+        OrderType::Limit => 1,
+        OrderType::StopMarket => 2,
+        OrderType::StopLimit => 3,
+        OrderType::TrailingStop => 4,
+    }
+}
+
+#[inline(always)]
+fn decode_order_type(value: u8) -> Result<OrderType, CodecError> {
+    match value {
+        0 => Ok(OrderType::Market),
+        1 => Ok(OrderType::Limit),
+        2 => Ok(OrderType::StopMarket),
+        3 => Ok(OrderType::StopLimit),
+        4 => Ok(OrderType::TrailingStop),
+        _ => invalid("order type", value),
+    }
+}
+
+#[inline(always)]
+fn encode_time_in_force(time_in_force: TimeInForce) -> u8 {
+    match time_in_force {
+        TimeInForce::GTC => 0,
+        TimeInForce::IOC => 1,
+        TimeInForce::FOK => 2,
+        TimeInForce::DAY => 3,
+        TimeInForce::GTD => 4,
+        TimeInForce::GTT => 5,
+    }
+}
+
+#[inline(always)]
+fn decode_time_in_force(value: u8) -> Result<TimeInForce, CodecError> {
+    match value {
+        0 => Ok(TimeInForce::GTC),
+        1 => Ok(TimeInForce::IOC),
+        2 => Ok(TimeInForce::FOK),
+        3 => Ok(TimeInForce::DAY),
+        4 => Ok(TimeInForce::GTD),
+        5 => Ok(TimeInForce::GTT),
+        _ => invalid("time in force", value),
+    }
+}
+
+#[inline(always)]
+fn encode_execution_condition(execution_condition: ExecutionCondition) -> u8 {
+    match execution_condition {
+        ExecutionCondition::None => 0,
+        ExecutionCondition::AON => 1,
+    }
+}
+
+#[inline(always)]
+fn decode_execution_condition(value: u8) -> Result<ExecutionCondition, CodecError> {
+    match value {
+        0 => Ok(ExecutionCondition::None),
+        1 => Ok(ExecutionCondition::AON),
+        _ => invalid("execution condition", value),
+    }
+}
+
+#[inline(always)]
+fn encode_post_only(post_only: PostOnly) -> u8 {
+    match post_only {
+        PostOnly::None => 0,
+        PostOnly::Reject => 1,
+        PostOnly::Slide => 2,
+    }
+}
+
+#[inline(always)]
+fn decode_post_only(value: u8) -> Result<PostOnly, CodecError> {
+    match value {
+        0 => Ok(PostOnly::None),
+        1 => Ok(PostOnly::Reject),
+        2 => Ok(PostOnly::Slide),
+        _ => invalid("post-only", value),
+    }
+}
+
+#[inline(always)]
+fn encode_self_trade_prevention(self_trade_prevention: SelfTradePrevention) -> u8 {
+    match self_trade_prevention {
+        SelfTradePrevention::None => 0,
+        SelfTradePrevention::CancelNewest => 1,
+        SelfTradePrevention::CancelOldest => 2,
+        SelfTradePrevention::CancelBoth => 3,
+        SelfTradePrevention::DecrementAndCancel => 4,
+    }
+}
+
+#[inline(always)]
+fn decode_self_trade_prevention(value: u8) -> Result<SelfTradePrevention, CodecError> {
+    match value {
+        0 => Ok(SelfTradePrevention::None),
+        1 => Ok(SelfTradePrevention::CancelNewest),
+        2 => Ok(SelfTradePrevention::CancelOldest),
+        3 => Ok(SelfTradePrevention::CancelBoth),
+        4 => Ok(SelfTradePrevention::DecrementAndCancel),
+        _ => invalid("self-trade prevention", value),
+    }
+}
+
+#[inline(always)]
+pub fn encode_oco_mode(mode: OcoMode) -> u8 {
+    match mode {
+        OcoMode::Cancel => 0,
+        OcoMode::Reduce => 1,
+    }
+}
+
+#[inline(always)]
+pub fn decode_oco_mode(value: u8) -> Result<OcoMode, CodecError> {
+    match value {
+        0 => Ok(OcoMode::Cancel),
+        1 => Ok(OcoMode::Reduce),
+        _ => invalid("oco mode", value),
+    }
+}
+
+// Tag followed by the value, the value is written even when unused to keep the size fixed
+fn encode_trailing_offset(encoder: &mut Encoder, trailing_offset: Option<TrailingOffset>) {
+    let (tag, value) = match trailing_offset {
+        None => (0, 0),
+        Some(TrailingOffset::Absolute(offset)) => (1, offset),
+        Some(TrailingOffset::BasisPoints(bps)) => (2, bps as u64),
+    };
+
+    encoder.put_u8(tag);
+    encoder.put_u64(value);
+}
+
+fn decode_trailing_offset(decoder: &mut Decoder) -> Result<Option<TrailingOffset>, CodecError> {
+    let tag = decoder.get_u8()?;
+    let value = decoder.get_u64()?;
+
+    match tag {
+        0 => Ok(None),
+        1 => Ok(Some(TrailingOffset::Absolute(value))),
+        2 => Ok(Some(TrailingOffset::BasisPoints(value as u32))),
+        _ => invalid("trailing offset", tag),
+    }
+}
+
+fn encode_market_protection(encoder: &mut Encoder, market_protection: MarketProtection) {
+    let (tag, value) = match market_protection {
+        MarketProtection::None => (0, 0),
+        MarketProtection::Ticks(ticks) => (1, ticks),
+        MarketProtection::BasisPoints(bps) => (2, bps),
+    };
+
+    encoder.put_u8(tag);
+    encoder.put_u32(value);
+}
+
+fn decode_market_protection(decoder: &mut Decoder) -> Result<MarketProtection, CodecError> {
+    let tag = decoder.get_u8()?;
+    let value = decoder.get_u32()?;
+
+    match tag {
+        0 => Ok(MarketProtection::None),
+        1 => Ok(MarketProtection::Ticks(value)),
+        2 => Ok(MarketProtection::BasisPoints(value)),
+        _ => invalid("market protection", tag),
+    }
+}
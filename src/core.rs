@@ -1,7 +1,16 @@
+pub mod exchange;
+pub mod instrument_spec;
 pub mod order;
 pub mod order_book;
+pub mod order_depth;
 pub mod order_error;
+pub mod order_event;
+pub mod order_link;
+pub mod order_listener;
 pub mod order_map;
 pub mod order_match;
+pub mod order_replace;
+pub mod order_snapshot;
 pub mod order_spec;
 pub mod orders;
+pub mod trailing_stops;
@@ -0,0 +1,293 @@
+use crate::{
+    clock::{Clock, ManualClock},
+    core::instrument_spec::InstrumentSpec,
+    core::order::{Order, OrderId, Price, Quantity, Timestamp},
+    core::order_book::OrderBook,
+    core::order_error::OrderError,
+    core::order_link::OcoMode,
+    core::order_listener::OrderListener,
+    core::order_match::OrderMatch,
+    core::order_replace::ReplaceOutcome,
+    utils::IdHashMap,
+};
+
+pub type SymbolId = u32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradingStatus {
+    Open,   // Accepting orders
+    Halted, // Temporarily stopped, resting orders can still be cancelled
+    Closed, // Out of trading hours, resting orders can still be cancelled
+}
+
+impl TradingStatus {
+    #[inline(always)]
+    pub fn is_open(self) -> bool {
+        self == TradingStatus::Open
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolConfig {
+    pub instrument_spec: InstrumentSpec,
+    pub status: TradingStatus,
+}
+
+impl Default for SymbolConfig {
+    fn default() -> Self {
+        Self {
+            instrument_spec: InstrumentSpec::default(),
+            status: TradingStatus::Open,
+        }
+    }
+}
+
+// Activity of a single symbol since it was added
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SymbolStats {
+    pub order_count: u64,
+    pub trade_count: u64,
+    pub traded_quantity: Quantity,
+}
+
+// Activity and resting orders summed over every symbol
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExchangeStats {
+    pub symbol_count: usize,
+    pub resting_orders: usize,
+    pub order_count: u64,
+    pub trade_count: u64,
+    pub traded_quantity: Quantity,
+}
+
+struct Market<T: Order, L: OrderListener, C: Clock> {
+    config: SymbolConfig,
+    book: OrderBook<T, L, C>,
+    stats: SymbolStats,
+}
+
+impl<T: Order, L: OrderListener, C: Clock> Market<T, L, C> {
+    #[inline(always)]
+    fn check_open(&self) -> Result<(), OrderError> {
+        if !self.config.status.is_open() {
+            return Err(OrderError::TradingNotOpen);
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn record_matches(&mut self, matches: &[OrderMatch]) {
+        self.stats.trade_count += matches.len() as u64;
+        self.stats.traded_quantity += matches.iter().map(|m| m.quantity).sum::<Quantity>();
+    }
+}
+
+/// Order books of many symbols, orders are routed to the book of their symbol
+pub struct Exchange<T: Order, L: OrderListener = (), C: Clock + Clone = ManualClock> {
+    markets: IdHashMap<SymbolId, Market<T, L, C>>,
+    expected_peak_order: usize,
+    clock: C,
+}
+
+impl<T: Order, L: OrderListener, C: Clock + Clone + Default> Exchange<T, L, C> {
+    // Every book is created with the same expected peak order
+    pub fn new(expected_peak_order: usize) -> Self {
+        Self::with_clock(expected_peak_order, C::default())
+    }
+}
+
+impl<T: Order, L: OrderListener, C: Clock + Clone> Exchange<T, L, C> {
+    // Every book gets a copy of the clock
+    pub fn with_clock(expected_peak_order: usize, clock: C) -> Self {
+        Exchange {
+            markets: IdHashMap::default(),
+            expected_peak_order,
+            clock,
+        }
+    }
+
+    pub fn add_symbol_with_listener(
+        &mut self,
+        symbol: SymbolId,
+        config: SymbolConfig,
+        listener: L,
+    ) -> Result<(), OrderError> {
+        if self.markets.contains_key(&symbol) {
+            return Err(OrderError::DuplicateSymbol);
+        }
+
+        let mut book = OrderBook::with_listener_and_clock(
+            self.expected_peak_order,
+            listener,
+            self.clock.clone(),
+        );
+        book.set_instrument_spec(config.instrument_spec);
+        self.markets.insert(
+            symbol,
+            Market {
+                config,
+                book,
+                stats: SymbolStats::default(),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Remove the symbol along with its book
+    pub fn remove_symbol(&mut self, symbol: SymbolId) -> Option<OrderBook<T, L, C>> {
+        self.markets.remove(&symbol).map(|market| market.book)
+    }
+
+    #[inline(always)]
+    pub fn symbols(&self) -> impl Iterator<Item = SymbolId> + '_ {
+        self.markets.keys().copied()
+    }
+
+    #[inline(always)]
+    pub fn book(&self, symbol: SymbolId) -> Option<&OrderBook<T, L, C>> {
+        self.markets.get(&symbol).map(|market| &market.book)
+    }
+
+    #[inline(always)]
+    pub fn config(&self, symbol: SymbolId) -> Option<&SymbolConfig> {
+        self.markets.get(&symbol).map(|market| &market.config)
+    }
+
+    pub fn set_config(&mut self, symbol: SymbolId, config: SymbolConfig) -> Result<(), OrderError> {
+        let market = self.market_mut(symbol)?;
+        market.book.set_instrument_spec(config.instrument_spec);
+        market.config = config;
+
+        Ok(())
+    }
+
+    pub fn set_trading_status(
+        &mut self,
+        symbol: SymbolId,
+        status: TradingStatus,
+    ) -> Result<(), OrderError> {
+        self.market_mut(symbol)?.config.status = status;
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn stats(&self, symbol: SymbolId) -> Option<&SymbolStats> {
+        self.markets.get(&symbol).map(|market| &market.stats)
+    }
+
+    pub fn exchange_stats(&self) -> ExchangeStats {
+        let mut exchange_stats = ExchangeStats {
+            symbol_count: self.markets.len(),
+            ..Default::default()
+        };
+
+        for market in self.markets.values() {
+            exchange_stats.resting_orders += market.book.order_allocator().len();
+            exchange_stats.order_count += market.stats.order_count;
+            exchange_stats.trade_count += market.stats.trade_count;
+            exchange_stats.traded_quantity += market.stats.traded_quantity;
+        }
+
+        exchange_stats
+    }
+
+    /// Orders of a symbol not open for trading never reach the book, so the book reports no event
+    pub fn insert_order(
+        &mut self,
+        symbol: SymbolId,
+        order: &T,
+    ) -> Result<Vec<OrderMatch>, OrderError> {
+        let market = self.market_mut(symbol)?;
+        market.check_open()?;
+
+        let matches = market.book.insert_order(order)?;
+        market.stats.order_count += 1;
+        market.record_matches(&matches);
+
+        Ok(matches)
+    }
+
+    pub fn insert_oco_order(
+        &mut self,
+        symbol: SymbolId,
+        order: &T,
+        linked_order: &T,
+        mode: OcoMode,
+    ) -> Result<Vec<OrderMatch>, OrderError> {
+        let market = self.market_mut(symbol)?;
+        market.check_open()?;
+
+        let matches = market.book.insert_oco_order(order, linked_order, mode)?;
+        market.stats.order_count += 2;
+        market.record_matches(&matches);
+
+        Ok(matches)
+    }
+
+    pub fn replace_order_by_id(
+        &mut self,
+        symbol: SymbolId,
+        order_id: OrderId,
+        quantity_delta: i64,
+        new_price: Price,
+    ) -> Result<ReplaceOutcome, OrderError> {
+        let market = self.market_mut(symbol)?;
+        market.check_open()?;
+
+        let outcome = market
+            .book
+            .replace_order_by_id(order_id, quantity_delta, new_price)?;
+        if let ReplaceOutcome::Requeued { matches } | ReplaceOutcome::Filled { matches } = &outcome
+        {
+            market.record_matches(matches);
+        }
+
+        Ok(outcome)
+    }
+
+    // Cancel is allowed whatever the trading status is
+    pub fn cancel_order_by_id(
+        &mut self,
+        symbol: SymbolId,
+        order_id: OrderId,
+    ) -> Result<T, OrderError> {
+        self.market_mut(symbol)?.book.cancel_order_by_id(order_id)
+    }
+
+    /// End of the trading day of the symbol, see `OrderBook::set_day_end`
+    pub fn set_day_end(&mut self, symbol: SymbolId, day_end: Timestamp) -> Result<(), OrderError> {
+        self.market_mut(symbol)?.book.set_day_end(day_end);
+        Ok(())
+    }
+
+    // Expiry is allowed whatever the trading status is, like a cancel
+    pub fn advance_time(&mut self, symbol: SymbolId, now: Timestamp) -> Result<Vec<T>, OrderError> {
+        Ok(self.market_mut(symbol)?.book.advance_time(now))
+    }
+
+    pub fn trigger_stop_orders(&mut self, symbol: SymbolId) -> Result<Vec<OrderMatch>, OrderError> {
+        let market = self.market_mut(symbol)?;
+        market.check_open()?;
+
+        let matches = market.book.trigger_stop_orders();
+        market.record_matches(&matches);
+
+        Ok(matches)
+    }
+
+    #[inline(always)]
+    fn market_mut(&mut self, symbol: SymbolId) -> Result<&mut Market<T, L, C>, OrderError> {
+        self.markets
+            .get_mut(&symbol)
+            .ok_or(OrderError::SymbolNotFound)
+    }
+}
+
+impl<T: Order, L: OrderListener + Default, C: Clock + Clone> Exchange<T, L, C> {
+    pub fn add_symbol(&mut self, symbol: SymbolId, config: SymbolConfig) -> Result<(), OrderError> {
+        self.add_symbol_with_listener(symbol, config, L::default())
+    }
+}
@@ -0,0 +1,107 @@
+use crate::core::{
+    order::{Order, Price, Quantity},
+    order_error::OrderError,
+};
+
+/// Trading rules of the instrument, enforced by the book on insert and replace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstrumentSpec {
+    pub tick_size: Price,   // Minimum price movement
+    pub lot_size: Quantity, // Quantity must be a multiple of it
+    pub min_quantity: Quantity,
+    pub max_quantity: Quantity,
+    pub min_price: Price,
+    pub max_price: Price,
+    pub min_notional: u128, // Minimum price * quantity of priced orders
+}
+
+impl Default for InstrumentSpec {
+    fn default() -> Self {
+        Self {
+            tick_size: 1,
+            lot_size: 1,
+            min_quantity: 1,
+            max_quantity: Quantity::MAX,
+            min_price: 1,
+            max_price: Price::MAX,
+            min_notional: 0,
+        }
+    }
+}
+
+impl InstrumentSpec {
+    pub fn validate_order<T: Order>(&self, order: &T) -> Result<(), OrderError> {
+        self.validate_quantity(order.quantity())?;
+
+        let order_type = order.order_type();
+        if order_type.is_stop_market() || order_type.is_stop_limit() {
+            self.validate_tick(order.stop_price())?;
+        }
+
+        if order_type.is_limit() || order_type.is_stop_limit() {
+            self.validate_price(order.price())?;
+            self.validate_notional(order.price(), order.quantity())?;
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn validate_quantity(&self, quantity: Quantity) -> Result<(), OrderError> {
+        if quantity == 0 {
+            return Err(OrderError::InvalidQuantity);
+        }
+
+        if self.lot_size > 1 && !quantity.is_multiple_of(self.lot_size) {
+            return Err(OrderError::InvalidLotSize);
+        }
+
+        if quantity < self.min_quantity {
+            return Err(OrderError::QuantityBelowMinimum);
+        }
+
+        if quantity > self.max_quantity {
+            return Err(OrderError::QuantityAboveMaximum);
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn validate_price(&self, price: Price) -> Result<(), OrderError> {
+        self.validate_tick(price)?;
+
+        if price < self.min_price {
+            return Err(OrderError::PriceBelowMinimum);
+        }
+
+        if price > self.max_price {
+            return Err(OrderError::PriceAboveMaximum);
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn validate_notional(&self, price: Price, quantity: Quantity) -> Result<(), OrderError> {
+        if (price as u128) * (quantity as u128) < self.min_notional {
+            return Err(OrderError::NotionalBelowMinimum);
+        }
+
+        Ok(())
+    }
+
+    // Price, including stop price, must be set and sit on the tick
+    #[inline(always)]
+    fn validate_tick(&self, price: Price) -> Result<(), OrderError> {
+        if price == 0 {
+            return Err(OrderError::InvalidPrice);
+        }
+
+        if self.tick_size > 1 && !price.is_multiple_of(self.tick_size) {
+            return Err(OrderError::InvalidTickSize);
+        }
+
+        Ok(())
+    }
+}
@@ -1,11 +1,19 @@
+use std::cmp;
+
 pub type Price = u64;
 pub type Quantity = u64;
 pub type OrderId = u32;
+pub type OwnerId = u32;
+pub type Timestamp = u64;
 
 pub trait Order: Clone {
     // id
     fn id(&self) -> OrderId;
 
+    // Account owning the order
+    fn owner_id(&self) -> OwnerId;
+    fn set_owner_id(&mut self, owner_id: OwnerId);
+
     // Price of ther order
     fn price(&self) -> Price;
     fn set_price(&mut self, new_price: Price);
@@ -19,11 +27,41 @@ pub trait Order: Clone {
 
     // Order Type
     fn order_type(&self) -> OrderType;
+    fn set_order_type(&mut self, order_type: OrderType);
+
+    // Stop price, only used by stop orders
+    fn stop_price(&self) -> Price;
+    fn set_stop_price(&mut self, stop_price: Price);
+
+    // Distance of trailing stop from the market price
+    fn trailing_offset(&self) -> Option<TrailingOffset>;
+
+    // Post-only, only add liquidity to the book
+    fn post_only(&self) -> PostOnly;
+    fn set_post_only(&mut self, post_only: PostOnly);
+
+    // Self-trade prevention, applied when the order takes liquidity
+    fn self_trade_prevention(&self) -> SelfTradePrevention;
+    fn set_self_trade_prevention(&mut self, self_trade_prevention: SelfTradePrevention);
+
+    // Market protection, limits how far a market order may trade from the best price
+    fn market_protection(&self) -> MarketProtection;
+    fn set_market_protection(&mut self, market_protection: MarketProtection);
+
+    // Expire time of DAY, GTD and GTT orders, 0 when not set
+    fn expire_time(&self) -> Timestamp;
+    fn set_expire_time(&mut self, expire_time: Timestamp);
+
+    // Time the order was accepted by the book, stamped by the book
+    fn accept_time(&self) -> Timestamp;
+    fn set_accept_time(&mut self, accept_time: Timestamp);
+
     // Time Force & Execution Condition
     fn time_in_force(&self) -> TimeInForce;
 
     // Execution Condition
     fn execution_condition(&self) -> ExecutionCondition;
+    fn set_execution_condition(&mut self, execution_condition: ExecutionCondition);
 
     // Set TimeInForce
     fn set_time_in_force(&mut self, time_in_force: TimeInForce);
@@ -35,6 +73,48 @@ pub trait Order: Clone {
         return self;
     }
 
+    // Copy ExecutionCondition
+    #[inline(always)]
+    fn with_execution_condition(mut self, execution_condition: ExecutionCondition) -> Self {
+        self.set_execution_condition(execution_condition);
+        self
+    }
+
+    // Copy PostOnly
+    #[inline(always)]
+    fn with_post_only(mut self, post_only: PostOnly) -> Self {
+        self.set_post_only(post_only);
+        self
+    }
+
+    // Copy OwnerId
+    #[inline(always)]
+    fn with_owner_id(mut self, owner_id: OwnerId) -> Self {
+        self.set_owner_id(owner_id);
+        self
+    }
+
+    // Copy SelfTradePrevention
+    #[inline(always)]
+    fn with_self_trade_prevention(mut self, self_trade_prevention: SelfTradePrevention) -> Self {
+        self.set_self_trade_prevention(self_trade_prevention);
+        self
+    }
+
+    // Copy MarketProtection
+    #[inline(always)]
+    fn with_market_protection(mut self, market_protection: MarketProtection) -> Self {
+        self.set_market_protection(market_protection);
+        self
+    }
+
+    // Copy expire time
+    #[inline(always)]
+    fn with_expire_time(mut self, expire_time: Timestamp) -> Self {
+        self.set_expire_time(expire_time);
+        self
+    }
+
     #[inline(always)]
     fn with_price(mut self, new_price: Price) -> Self {
         self.set_price(new_price);
@@ -47,6 +127,12 @@ pub trait Order: Clone {
         return self;
     }
 
+    #[inline(always)]
+    fn with_stop_price(mut self, stop_price: Price) -> Self {
+        self.set_stop_price(stop_price);
+        self
+    }
+
     // Order Side
     #[inline(always)]
     fn is_buy(&self) -> bool {
@@ -68,12 +154,27 @@ pub trait Order: Clone {
         self.order_type() == OrderType::Limit
     }
 
+    // Stop order waiting for its stop price to be hit
+    #[inline(always)]
+    fn is_stop(&self) -> bool {
+        self.order_type().is_stop()
+    }
+
     // Good Till Cancel
     #[inline(always)]
     fn good_till_cancel(&self) -> bool {
         return self.time_in_force() == TimeInForce::GTC;
     }
 
+    // Good for the day, till date or till time, cancelled once expired
+    #[inline(always)]
+    fn is_expirable(&self) -> bool {
+        matches!(
+            self.time_in_force(),
+            TimeInForce::DAY | TimeInForce::GTD | TimeInForce::GTT
+        )
+    }
+
     // should not lived at slab allocator because we discard the order from the system immediately
     #[inline(always)]
     fn is_ephemeral_order(&self) -> bool {
@@ -91,9 +192,16 @@ pub trait Order: Clone {
     fn is_fill_or_kill(&self) -> bool {
         return self.time_in_force() == TimeInForce::FOK;
     }
+
+    // is all or none, only filled with the full quantity
+    #[inline(always)]
+    fn is_all_or_none(&self) -> bool {
+        self.execution_condition() == ExecutionCondition::AON
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OrderSide {
     Buy,  // Buy order
     Sell, // Sell order
@@ -109,9 +217,19 @@ impl OrderSide {
     pub fn is_sell(self) -> bool {
         return self == OrderSide::Sell;
     }
+
+    #[inline(always)]
+    pub fn opposite(self) -> OrderSide {
+        if self.is_buy() {
+            OrderSide::Sell
+        } else {
+            OrderSide::Buy
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TimeInForce {
     GTC, // Good till cancel
     IOC, // Immediate or cancel
@@ -122,12 +240,112 @@ pub enum TimeInForce {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExecutionCondition {
     None, // No condition
     AON,  // All-Or-None
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PostOnly {
+    None,   // Allowed to take liquidity
+    Reject, // Rejected when crossing the spread
+    Slide,  // Repriced one tick away from the best price at the other side
+}
+
+impl PostOnly {
+    #[inline(always)]
+    pub fn is_none(&self) -> bool {
+        *self == PostOnly::None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SelfTradePrevention {
+    None,               // Allowed to trade with orders of the same owner
+    CancelNewest,       // Cancel the incoming order
+    CancelOldest,       // Cancel the resting order
+    CancelBoth,         // Cancel both orders
+    DecrementAndCancel, // Decrease both orders by the smaller quantity, cancelling the smaller one
+}
+
+impl SelfTradePrevention {
+    #[inline(always)]
+    pub fn is_none(&self) -> bool {
+        *self == SelfTradePrevention::None
+    }
+}
+
+// Market order with protection trades as a limit order at the protection price,
+// the remaining quantity rests there unless the order is immediate-or-cancel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MarketProtection {
+    None,             // Sweep the book without any price limit
+    Ticks(u32),       // Number of ticks away from the best price at arrival
+    BasisPoints(u32), // Distance relative to the best price at arrival, 1 bps = 0.01%
+}
+
+impl MarketProtection {
+    #[inline(always)]
+    pub fn is_none(&self) -> bool {
+        *self == MarketProtection::None
+    }
+
+    // Worst price the order may trade at, staying on the tick
+    #[inline(always)]
+    pub fn protection_price(
+        &self,
+        order_side: OrderSide,
+        best_price: Price,
+        tick_size: Price,
+    ) -> Price {
+        let tick_size = cmp::max(tick_size, 1);
+        let offset = match *self {
+            MarketProtection::None => return best_price,
+            MarketProtection::Ticks(ticks) => (ticks as Price).saturating_mul(tick_size),
+            MarketProtection::BasisPoints(bps) => {
+                let offset = best_price.saturating_mul(bps as Price) / 10_000;
+                offset - offset % tick_size
+            }
+        };
+
+        if order_side.is_buy() {
+            best_price.saturating_add(offset)
+        } else {
+            best_price.saturating_sub(offset)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TrailingOffset {
+    Absolute(Price),  // Fixed price distance from the market price
+    BasisPoints(u32), // Distance relative to the market price, 1 bps = 0.01%
+}
+
+impl TrailingOffset {
+    // Stop price trailing the given market price
+    #[inline(always)]
+    pub fn stop_price(&self, order_side: OrderSide, market_price: Price) -> Price {
+        let offset = match *self {
+            TrailingOffset::Absolute(offset) => offset,
+            TrailingOffset::BasisPoints(bps) => market_price.saturating_mul(bps as Price) / 10_000,
+        };
+
+        if order_side.is_buy() {
+            market_price.saturating_add(offset)
+        } else {
+            market_price.saturating_sub(offset)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OrderType {
     Market,       // Market order — match now, no price
     Limit,        // Limit order — match at limit price or better
@@ -161,4 +379,9 @@ impl OrderType {
     pub fn is_trailing_stop(&self) -> bool {
         *self == OrderType::TrailingStop
     }
+
+    #[inline(always)]
+    pub fn is_stop(&self) -> bool {
+        self.is_stop_market() || self.is_stop_limit() || self.is_trailing_stop()
+    }
 }
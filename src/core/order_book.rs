@@ -1,18 +1,32 @@
-use std::cmp;
+use std::{cmp, fmt::Display, mem};
 
 use slab::Slab;
 use tabled::{builder::Builder, settings::Style};
 
 use crate::{
-    core::order::{self, Order, OrderId, OrderSide, Price, Quantity},
+    clock::{Clock, ManualClock},
+    codec::{CodecError, Decoder, Encoder, OrderCodec, decode_oco_mode, encode_oco_mode},
+    core::instrument_spec::InstrumentSpec,
+    core::order::{
+        self, Order, OrderId, OrderSide, OrderType, PostOnly, Price, Quantity, SelfTradePrevention,
+        TimeInForce, Timestamp,
+    },
+    core::order_depth::{BookDepth, BookOrder, DepthCache, DepthChange, DepthFeed, PriceLevel},
     core::order_error::OrderError,
+    core::order_event::{CancelReason, LiquidityRole, OrderEvent},
+    core::order_link::{OcoMode, OrderLink},
+    core::order_listener::{EventSink, OrderListener},
     core::order_map::OrderMap,
     core::order_match::OrderMatch,
-    core::orders::{Orders, SlabIndex},
-    utils::ReverseOrd,
+    core::order_replace::ReplaceOutcome,
+    core::order_snapshot::{SNAPSHOT_MAGIC, SNAPSHOT_VERSION, SnapshotError},
+    core::orders::{OrderLocation, SlabIndex},
+    core::trailing_stops::TrailingStops,
+    schedular::ExpiryScheduler,
+    utils::{IdHashMap, IdHashSet, ReverseOrd, crc32},
 };
 
-pub struct OrderBook<T: Order> {
+pub struct OrderBook<T: Order, L: OrderListener = (), C: Clock = ManualClock> {
     // Memory Allocator
     order_allocator: slab::Slab<T>,
 
@@ -24,21 +38,99 @@ pub struct OrderBook<T: Order> {
     stop_bids: OrderMap<ReverseOrd<Price>>,
     stop_asks: OrderMap<Price>,
 
-    // Price
+    // Trailing stop orders living at stop bids and stop asks, by order id so they
+    // trail in the same order whatever slot of the allocator they live in
+    trailing_stops: TrailingStops,
+    trailing_market_price: Price,
+
+    // Resting orders by id, including stop orders
+    order_index: IdHashMap<OrderId, OrderLocation>,
+
+    // All-Or-None orders resting at bids and asks
+    aon_bids: usize,
+    aon_asks: usize,
+
+    // One-Cancels-Other legs and their fills waiting to be processed
+    order_links: IdHashMap<OrderId, OrderLink>,
+    linked_fills: Vec<(OrderId, Quantity)>,
+
+    // Top price levels maintained on every change, when enabled
+    depth_cache: Option<DepthCache>,
+
+    // Level changes of the last operation, when enabled
+    depth_feed: Option<DepthFeed>,
+
+    // Events of the last operation, forwarded to the listener
+    events: EventSink<L>,
+
+    // Tick size, lot size and limits enforced on insert and replace
+    instrument_spec: InstrumentSpec,
+
+    // Resting DAY, GTD and GTT orders by expire time
+    expiry_scheduler: ExpiryScheduler,
+    current_time: Timestamp,
+    day_end: Timestamp,
+
+    // Time of the last operation, stamped on accepted orders and fills
+    clock: C,
+    transact_time: Timestamp,
+
+    // Last Trade
     current_market_price: Price,
+    last_trade_quantity: Quantity,
 }
 
-// Public Function
 impl<T: Order> OrderBook<T> {
     #[inline(always)]
     pub fn new(expected_peak_order: usize) -> Self {
+        Self::with_listener(expected_peak_order, ())
+    }
+}
+
+impl<T: Order, L: OrderListener> OrderBook<T, L> {
+    /// Book notifying the listener of every event as it happens
+    #[inline(always)]
+    pub fn with_listener(expected_peak_order: usize, listener: L) -> Self {
+        Self::with_listener_and_clock(expected_peak_order, listener, ManualClock::default())
+    }
+}
+
+impl<T: Order, C: Clock> OrderBook<T, (), C> {
+    /// Book taking the time of every operation from the clock
+    #[inline(always)]
+    pub fn with_clock(expected_peak_order: usize, clock: C) -> Self {
+        Self::with_listener_and_clock(expected_peak_order, (), clock)
+    }
+}
+
+// Public Function
+impl<T: Order, L: OrderListener, C: Clock> OrderBook<T, L, C> {
+    #[inline(always)]
+    pub fn with_listener_and_clock(expected_peak_order: usize, listener: L, clock: C) -> Self {
         return OrderBook {
             order_allocator: slab::Slab::with_capacity(expected_peak_order),
             asks: OrderMap::new(),
             bids: OrderMap::new(),
             stop_asks: OrderMap::new(),
             stop_bids: OrderMap::new(),
+            trailing_stops: TrailingStops::new(),
+            trailing_market_price: 0,
+            order_index: IdHashMap::default(),
+            aon_bids: 0,
+            aon_asks: 0,
+            order_links: IdHashMap::default(),
+            linked_fills: Vec::new(),
+            depth_cache: None,
+            depth_feed: None,
+            events: EventSink::new(listener),
+            instrument_spec: InstrumentSpec::default(),
+            expiry_scheduler: ExpiryScheduler::new(),
+            current_time: 0,
+            day_end: 0,
+            clock,
+            transact_time: 0,
             current_market_price: 0,
+            last_trade_quantity: 0,
         };
     }
 
@@ -51,257 +143,1362 @@ impl<T: Order> OrderBook<T> {
         self.current_market_price = current_market_price
     }
 
-    #[inline(always)]
-    pub fn asks(&self) -> &OrderMap<Price> {
-        return &self.asks;
+    #[inline(always)]
+    pub fn tick_size(&self) -> Price {
+        self.instrument_spec.tick_size
+    }
+
+    pub fn set_tick_size(&mut self, tick_size: Price) {
+        self.instrument_spec.tick_size = tick_size
+    }
+
+    #[inline(always)]
+    pub fn instrument_spec(&self) -> &InstrumentSpec {
+        &self.instrument_spec
+    }
+
+    pub fn set_instrument_spec(&mut self, instrument_spec: InstrumentSpec) {
+        self.instrument_spec = instrument_spec
+    }
+
+    #[inline(always)]
+    pub fn last_trade_quantity(&self) -> Quantity {
+        self.last_trade_quantity
+    }
+
+    #[inline(always)]
+    pub fn asks(&self) -> &OrderMap<Price> {
+        return &self.asks;
+    }
+
+    #[inline(always)]
+    pub fn bids(&self) -> &OrderMap<ReverseOrd<Price>> {
+        return &self.bids;
+    }
+
+    #[inline(always)]
+    pub fn stop_asks(&self) -> &OrderMap<Price> {
+        return &self.stop_asks;
+    }
+
+    #[inline(always)]
+    pub fn stop_bids(&self) -> &OrderMap<ReverseOrd<Price>> {
+        return &self.stop_bids;
+    }
+
+    #[inline(always)]
+    pub fn order_allocator(&self) -> &Slab<T> {
+        return &self.order_allocator;
+    }
+
+    /// Resting bids in priority order, best price first then time
+    pub fn bid_orders(&self) -> impl Iterator<Item = BookOrder<'_, T>> {
+        Self::book_orders(&self.bids, &self.order_allocator, |key| key.0)
+    }
+
+    /// Resting asks in priority order, best price first then time
+    pub fn ask_orders(&self) -> impl Iterator<Item = BookOrder<'_, T>> {
+        Self::book_orders(&self.asks, &self.order_allocator, |key| *key)
+    }
+
+    /// Top `depth` aggregated price levels of both sides, walking the book
+    pub fn depth(&self, depth: usize) -> BookDepth {
+        BookDepth {
+            bids: Self::price_levels(&self.bids, depth, |key| key.0),
+            asks: Self::price_levels(&self.asks, depth, |key| *key),
+        }
+    }
+
+    /// Keep the top `depth` price levels up to date as the book changes, 0 disables the cache
+    pub fn set_depth_cache(&mut self, depth: usize) {
+        self.depth_cache = if depth > 0 {
+            Some(DepthCache::new(self.depth(depth), depth))
+        } else {
+            None
+        };
+    }
+
+    /// Cached top price levels, without walking the book
+    #[inline(always)]
+    pub fn depth_cache(&self) -> Option<&BookDepth> {
+        self.depth_cache.as_ref().map(|cache| cache.book_depth())
+    }
+
+    /// Record a change for every price level added, updated or removed at bids and asks
+    pub fn set_depth_feed(&mut self, enabled: bool) {
+        self.depth_feed = if enabled {
+            Some(self.depth_feed.take().unwrap_or_default())
+        } else {
+            None
+        };
+    }
+
+    /// Level changes of the last operation, in the order they happened
+    #[inline(always)]
+    pub fn depth_changes(&self) -> &[DepthChange] {
+        self.depth_feed.as_ref().map_or(&[], |feed| feed.changes())
+    }
+
+    /// Events emitted by the book during the last operation, beside the order matches
+    #[inline(always)]
+    pub fn events(&self) -> &[OrderEvent] {
+        self.events.events()
+    }
+
+    #[inline(always)]
+    pub fn listener(&self) -> &L {
+        self.events.listener()
+    }
+
+    #[inline(always)]
+    pub fn listener_mut(&mut self) -> &mut L {
+        self.events.listener_mut()
+    }
+
+    pub fn insert_order(&mut self, order: &T) -> Result<Vec<OrderMatch>, OrderError> {
+        self.clear_events();
+        let result = self
+            .instrument_spec
+            .validate_order(order)
+            .and_then(|_| self.validate_expire_time(order))
+            .and_then(|_| self.place_order(order, false));
+        self.reject_on_error(order.id(), result)
+    }
+
+    /// Insert One-Cancels-Other pair, typically a take-profit limit order with a stop-loss order.
+    /// Any fill on one leg cancels or reduces the other leg depending on `mode`,
+    /// reported through `events`. The first leg stays on its own when the linked leg is rejected.
+    pub fn insert_oco_order(
+        &mut self,
+        order: &T,
+        linked_order: &T,
+        mode: OcoMode,
+    ) -> Result<Vec<OrderMatch>, OrderError> {
+        self.clear_events();
+
+        if order.id() == linked_order.id()
+            || self.order_links.contains_key(&order.id())
+            || self.order_links.contains_key(&linked_order.id())
+        {
+            return self.reject_on_error(order.id(), Err(OrderError::OrderAlreadyLinked));
+        }
+
+        if self.order_index.contains_key(&linked_order.id()) {
+            return self.reject_on_error(linked_order.id(), Err(OrderError::DuplicateOrderId));
+        }
+
+        // Both legs follow the instrument rules before any of them trades
+        if let Err(err) = self
+            .instrument_spec
+            .validate_order(order)
+            .and_then(|_| self.validate_expire_time(order))
+        {
+            return self.reject_on_error(order.id(), Err(err));
+        }
+
+        if let Err(err) = self
+            .instrument_spec
+            .validate_order(linked_order)
+            .and_then(|_| self.validate_expire_time(linked_order))
+        {
+            return self.reject_on_error(linked_order.id(), Err(err));
+        }
+
+        self.order_links
+            .insert(order.id(), OrderLink::new(linked_order.id(), mode));
+        self.order_links
+            .insert(linked_order.id(), OrderLink::new(order.id(), mode));
+
+        let mut order_matches = match self.place_order(order, false) {
+            Ok(order_matches) => order_matches,
+            Err(err) => {
+                self.unlink_order(order.id());
+                return self.reject_on_error(order.id(), Err(err));
+            }
+        };
+
+        // First leg may trade on arrival, before the linked leg is at the book
+        let filled_quantity: Quantity = order_matches
+            .iter()
+            .filter(|m| m.match_from_id == order.id() || m.match_to_id == order.id())
+            .map(|m| m.quantity)
+            .sum();
+
+        // A done first leg already dropped its own link, the linked leg stands alone
+        if self.linked_order_idx(order.id()).is_none() {
+            self.order_links.remove(&order.id());
+            self.order_links.remove(&linked_order.id());
+        }
+
+        let linked_order = if filled_quantity == 0 {
+            linked_order.clone()
+        } else if mode == OcoMode::Reduce && filled_quantity < linked_order.quantity() {
+            let quantity = linked_order.quantity() - filled_quantity;
+            self.events.emit(OrderEvent::Reduced {
+                order_id: linked_order.id(),
+                quantity,
+            });
+
+            linked_order.clone().with_quantity(quantity)
+        } else {
+            // Linked leg was never accepted, so it's rejected rather than cancelled
+            self.order_links.remove(&order.id());
+            self.order_links.remove(&linked_order.id());
+            self.events.emit(OrderEvent::Rejected {
+                order_id: linked_order.id(),
+                reason: OrderError::LinkedOrderFilled,
+            });
+
+            return Ok(order_matches);
+        };
+
+        match self.place_order(&linked_order, false) {
+            Ok(linked_matches) => order_matches.extend(linked_matches),
+            Err(reason) => {
+                self.unlink_order(order.id());
+                self.events.emit(OrderEvent::Rejected {
+                    order_id: linked_order.id(),
+                    reason,
+                });
+            }
+        }
+
+        Ok(order_matches)
+    }
+
+    /// Replace the quantity and/or price of the resting order, `new_price` of 0 keeps the price.
+    /// `new_price` is the stop price of a stop order, a stop limit order keeps its limit price.
+    /// Quantity decrease keeps the time priority, price change or size increase loses it.
+    pub fn replace_order(
+        &mut self,
+        order: &T,
+        quantity_delta: i64,
+        new_price: Price,
+    ) -> Result<ReplaceOutcome, OrderError> {
+        self.replace_order_by_id(order.id(), quantity_delta, new_price)
+    }
+
+    pub fn replace_order_by_id(
+        &mut self,
+        order_id: OrderId,
+        quantity_delta: i64,
+        new_price: Price,
+    ) -> Result<ReplaceOutcome, OrderError> {
+        self.clear_events();
+        let result = self.replace_book_order(order_id, quantity_delta, new_price);
+        self.reject_on_error(order_id, result)
+    }
+
+    #[inline(always)]
+    pub fn current_time(&self) -> Timestamp {
+        self.current_time
+    }
+
+    #[inline(always)]
+    pub fn day_end(&self) -> Timestamp {
+        self.day_end
+    }
+
+    /// End of the trading day, DAY orders without an expire time expire at it,
+    /// including the ones already resting. Until it's set, they don't expire.
+    pub fn set_day_end(&mut self, day_end: Timestamp) {
+        self.day_end = day_end;
+        if day_end == 0 {
+            return;
+        }
+
+        for (_, order) in self.order_allocator.iter_mut() {
+            if order.time_in_force() == TimeInForce::DAY && order.expire_time() == 0 {
+                order.set_expire_time(day_end);
+                self.expiry_scheduler.schedule(day_end, order.id());
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    #[inline(always)]
+    pub fn clock_mut(&mut self) -> &mut C {
+        &mut self.clock
+    }
+
+    /// Time of the last operation taken from the clock
+    #[inline(always)]
+    pub fn transact_time(&self) -> Timestamp {
+        self.transact_time
+    }
+
+    /// Cancel the orders expired at the current time of the clock
+    pub fn expire_orders(&mut self) -> Vec<T> {
+        let now = self.clock.now();
+        self.advance_time(now)
+    }
+
+    /// Move the book time forward and cancel the orders expired by then,
+    /// reported through `events`. Time never goes backward.
+    pub fn advance_time(&mut self, now: Timestamp) -> Vec<T> {
+        self.clear_events();
+        self.current_time = cmp::max(self.current_time, now);
+
+        let mut expired_orders = Vec::new();
+        for (expire_time, order_id) in self.expiry_scheduler.pop_expired(self.current_time) {
+            // Order may have left the book, or the id was reused since
+            let is_resting = self
+                .get_order(order_id)
+                .is_some_and(|order| order.expire_time() == expire_time && order.is_expirable());
+            if is_resting && let Ok(order) = self.cancel_book_order(order_id, CancelReason::Expired)
+            {
+                expired_orders.push(order);
+            }
+        }
+
+        expired_orders
+    }
+
+    /// Cancel the resting order, only the id of the given order is used
+    pub fn cancel_order(&mut self, order: &T) -> Result<T, OrderError> {
+        self.cancel_order_by_id(order.id())
+    }
+
+    pub fn cancel_order_by_id(&mut self, order_id: OrderId) -> Result<T, OrderError> {
+        self.clear_events();
+        let result = self.cancel_book_order(order_id, CancelReason::Requested);
+        self.reject_on_error(order_id, result)
+    }
+
+    fn replace_book_order(
+        &mut self,
+        order_id: OrderId,
+        quantity_delta: i64,
+        new_price: Price,
+    ) -> Result<ReplaceOutcome, OrderError> {
+        let order_idx = self
+            .order_index
+            .get(&order_id)
+            .ok_or(OrderError::OrderNotFound)?
+            .slab_idx() as usize;
+
+        // Get Order inside Slab Allocator
+        let slab_order = self
+            .order_allocator
+            .get(order_idx)
+            .ok_or(OrderError::SlabOrderNotFound)?;
+
+        // New Order Quantity, delta can't take more than the order quantity
+        let new_quantity = if quantity_delta.is_negative() {
+            slab_order
+                .quantity()
+                .checked_sub(quantity_delta.unsigned_abs())
+        } else {
+            slab_order
+                .quantity()
+                .checked_add(quantity_delta as Quantity)
+        }
+        .ok_or(OrderError::InvalidQuantityDelta)?;
+
+        if new_quantity == 0 {
+            return Err(OrderError::OrderAlreadyFilled);
+        }
+
+        // Stop order replaces its stop price, trailing stop order takes it from the market
+        let is_stop = slab_order.is_stop();
+        let old_price = if is_stop {
+            slab_order.stop_price()
+        } else {
+            slab_order.price()
+        };
+        let is_price_changed = new_price != 0 && new_price != old_price;
+        if is_stop && is_price_changed && slab_order.order_type().is_trailing_stop() {
+            return Err(OrderError::TrailingStopPriceReplace);
+        }
+
+        // Replaced order follows the instrument rules as a new one does
+        self.instrument_spec.validate_quantity(new_quantity)?;
+        if is_stop && is_price_changed {
+            let new_order = slab_order
+                .clone()
+                .with_quantity(new_quantity)
+                .with_stop_price(new_price);
+            self.instrument_spec.validate_order(&new_order)?;
+        } else if slab_order.is_limit_price() || slab_order.order_type().is_stop_limit() {
+            let price = if is_price_changed {
+                self.instrument_spec.validate_price(new_price)?;
+                new_price
+            } else {
+                slab_order.price()
+            };
+            self.instrument_spec
+                .validate_notional(price, new_quantity)?;
+        }
+
+        // Quantity decrease keeps the order at its queue position
+        if !is_price_changed && quantity_delta <= 0 {
+            self.events.emit(OrderEvent::Replaced {
+                order_id,
+                price: slab_order.price(),
+                quantity: new_quantity,
+            });
+
+            let (order_side, price, is_aon) = (
+                slab_order.order_side(),
+                slab_order.price(),
+                !slab_order.is_stop() && slab_order.is_all_or_none(),
+            );
+            self.reduce_book_order(order_idx, quantity_delta.unsigned_abs());
+
+            // Smaller All-Or-None order may now be filled by the other side
+            if is_aon {
+                let mut matches = Vec::new();
+                self.match_aon_orders(order_side.opposite(), price, &mut matches);
+                if !self.linked_fills.is_empty() {
+                    self.process_linked_fills();
+                }
+
+                if !matches.is_empty() {
+                    self.release_stop_orders(&mut matches);
+                    return Ok(ReplaceOutcome::Filled { matches });
+                }
+            }
+
+            return Ok(ReplaceOutcome::InPlace {
+                quantity: new_quantity,
+            });
+        }
+
+        // Create New Order, keeping the attributes of the resting order
+        let mut new_order = slab_order.clone().with_quantity(new_quantity);
+        if is_price_changed && is_stop {
+            new_order = new_order.with_stop_price(new_price);
+        } else if is_price_changed {
+            new_order = new_order.with_price(new_price);
+        }
+
+        // Rejected post-only order should keep the resting order untouched
+        if new_order.is_limit_price() && !new_order.post_only().is_none() {
+            self.post_only_price(&new_order)?;
+        }
+
+        // Insert as new order at the back of the queue
+        self.remove_book_order(order_idx);
+        let matches = self.place_order(&new_order, true)?;
+        Ok(ReplaceOutcome::Requeued { matches })
+    }
+
+    fn cancel_book_order(
+        &mut self,
+        order_id: OrderId,
+        reason: CancelReason,
+    ) -> Result<T, OrderError> {
+        let order_idx = self
+            .order_index
+            .get(&order_id)
+            .ok_or(OrderError::OrderNotFound)?
+            .slab_idx() as usize;
+        self.events.emit(OrderEvent::Cancelled {
+            order_id,
+            quantity: self.order_allocator[order_idx].quantity(),
+            reason,
+        });
+        let slab_order = self.remove_book_order(order_idx);
+
+        // Cancelled leg no longer cancels the other leg
+        if !self.order_links.is_empty() {
+            self.unlink_order(order_id);
+        }
+
+        return Ok(slab_order);
+    }
+
+    /// Resting order with the given id, including stop orders
+    #[inline(always)]
+    pub fn get_order(&self, order_id: OrderId) -> Option<&T> {
+        let order_idx = self.order_index.get(&order_id)?.slab_idx() as usize;
+        self.order_allocator.get(order_idx)
+    }
+
+    // Insert a stop order into the order book, only through `place_order` which
+    // checks the id and validates the order. Stop orders are stored in stop_bids
+    // or stop_asks depending on side, keyed by their stop price. Trailing stop
+    // orders start trailing from the current market price.
+    fn insert_stop_order(&mut self, order: &T) {
+        let stop_price = self.stop_price(order);
+        let order_idx = self
+            .order_allocator
+            .insert(order.clone().with_stop_price(stop_price));
+        self.order_allocator[order_idx].set_accept_time(self.transact_time);
+        if order.order_type().is_trailing_stop() {
+            let market_price = match self.current_market_price {
+                0 if order.is_buy() => Price::MAX,
+                market_price => market_price,
+            };
+            self.trailing_stops
+                .insert(order.id(), order.order_side(), market_price);
+        }
+
+        // Add to stop order map
+        let position = if order.is_buy() {
+            let key = &ReverseOrd::new(stop_price);
+            self.stop_bids
+                .add_order(key, order_idx as SlabIndex, order.id(), order.quantity())
+        } else {
+            let key = &stop_price;
+            self.stop_asks
+                .add_order(key, order_idx as SlabIndex, order.id(), order.quantity())
+        };
+
+        self.order_index.insert(
+            order.id(),
+            OrderLocation::new(order_idx as SlabIndex, position),
+        );
+        if order.is_expirable() && order.expire_time() > 0 {
+            self.expiry_scheduler
+                .schedule(order.expire_time(), order.id());
+        }
+    }
+
+    pub fn recover_order_price(&self, order_side: OrderSide, order_id: OrderId) -> Option<Price> {
+        self.get_order(order_id)
+            .filter(|order| order.order_side() == order_side && !order.is_stop())
+            .map(|order| order.price())
+    }
+
+    /// Trigger stop orders if the market price crosses their stop price.
+    /// This is called after each trade, and should be called after `set_market_price`.
+    /// Triggered orders can trade and move the price again, so it keeps going
+    /// until no more stop order is hit.
+    pub fn trigger_stop_orders(&mut self) -> Vec<OrderMatch> {
+        self.clear_events();
+
+        let mut triggered_matches = Vec::new();
+        self.release_stop_orders(&mut triggered_matches);
+        triggered_matches
+    }
+
+    // Optional: Validate cache consistency
+    #[inline(always)]
+    pub fn validate_cache(&self) -> Result<(), String> {
+        self.asks.validate_cache()?;
+        self.bids.validate_cache()?;
+        self.stop_asks.validate_cache()?;
+        self.stop_bids.validate_cache()?;
+
+        if let Some(depth_cache) = &self.depth_cache {
+            let depth = self.depth(depth_cache.depth());
+            if depth_cache.book_depth() != &depth {
+                return Err(format!(
+                    "Depth cache mismatch: cached {:?}, calculated {:?}",
+                    depth_cache.book_depth(),
+                    depth
+                ));
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+// Snapshot of the resting orders, restored instead of replaying every command since the start
+impl<T: Order + OrderCodec, L: OrderListener, C: Clock> OrderBook<T, L, C> {
+    /// Serialize the resting orders per price level in queue order, stop orders,
+    /// links and market state. Layout, little endian:
+    /// `magic | version: u16 | next sequence: u64 | market state | bids | asks | stop bids | stop asks | links | crc32: u32`,
+    /// each side is `level count: u32` then `price: u64 | order count: u32 | orders` per level.
+    /// `next_sequence` is the first journal record not covered by the snapshot, replayed after restore.
+    /// The instrument spec, depth settings, listener and clock belong to the book and aren't included.
+    pub fn snapshot(&self, next_sequence: u64) -> Vec<u8> {
+        let mut encoder = Encoder::new();
+        SNAPSHOT_MAGIC.iter().for_each(|&byte| encoder.put_u8(byte));
+        encoder.put_u16(SNAPSHOT_VERSION);
+        encoder.put_u64(next_sequence);
+
+        encoder.put_u64(self.current_market_price);
+        encoder.put_u64(self.last_trade_quantity);
+        encoder.put_u64(self.trailing_market_price);
+        encoder.put_u64(self.current_time);
+        encoder.put_u64(self.day_end);
+
+        self.snapshot_levels(&mut encoder, &self.bids, |key| key.0);
+        self.snapshot_levels(&mut encoder, &self.asks, |key| *key);
+        self.snapshot_levels(&mut encoder, &self.stop_bids, |key| key.0);
+        self.snapshot_levels(&mut encoder, &self.stop_asks, |key| *key);
+
+        // Sorted so the same book always gives the same bytes
+        let mut order_links: Vec<(&OrderId, &OrderLink)> = self.order_links.iter().collect();
+        order_links.sort_by_key(|(order_id, _)| **order_id);
+        encoder.put_u32(order_links.len() as u32);
+        for (&order_id, link) in order_links {
+            encoder.put_u32(order_id);
+            encoder.put_u32(link.linked_id());
+            encoder.put_u8(encode_oco_mode(link.mode()));
+        }
+
+        let checksum = crc32(encoder.bytes());
+        encoder.put_u32(checksum);
+        encoder.into_bytes()
+    }
+
+    /// Replace the content of the book with the snapshot, returns the next journal sequence.
+    /// The snapshot is checked and decoded before the book is touched,
+    /// the rebuilt book is verified by `validate_cache`.
+    pub fn restore(&mut self, bytes: &[u8]) -> Result<u64, SnapshotError> {
+        let Some(body_len) = bytes.len().checked_sub(4) else {
+            return Err(CodecError::UnexpectedEnd.into());
+        };
+        let (body, checksum) = bytes.split_at(body_len);
+
+        let mut decoder = Decoder::new(body);
+        if decoder.take(SNAPSHOT_MAGIC.len())? != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::InvalidMagic);
+        }
+
+        let version = decoder.get_u16()?;
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        if crc32(body) != u32::from_le_bytes(checksum.try_into().unwrap()) {
+            return Err(SnapshotError::ChecksumMismatch);
+        }
+
+        let next_sequence = decoder.get_u64()?;
+        let current_market_price = decoder.get_u64()?;
+        let last_trade_quantity = decoder.get_u64()?;
+        let trailing_market_price = decoder.get_u64()?;
+        let current_time = decoder.get_u64()?;
+        let day_end = decoder.get_u64()?;
+
+        let mut levels = Vec::with_capacity(4);
+        for _ in 0..4 {
+            levels.push(Self::decode_levels(&mut decoder)?);
+        }
+
+        let link_count = decoder.get_u32()?;
+        let mut order_links = Vec::with_capacity(link_count as usize);
+        for _ in 0..link_count {
+            let order_id = decoder.get_u32()?;
+            let linked_id = decoder.get_u32()?;
+            let mode = decode_oco_mode(decoder.get_u8()?)?;
+            order_links.push((order_id, OrderLink::new(linked_id, mode)));
+        }
+
+        Self::check_levels(&levels)?;
+
+        self.clear_book();
+        self.current_market_price = current_market_price;
+        self.last_trade_quantity = last_trade_quantity;
+        self.trailing_market_price = trailing_market_price;
+        self.current_time = current_time;
+        self.day_end = day_end;
+
+        for (price, order) in levels.into_iter().flatten() {
+            self.restore_order(price, order);
+        }
+        self.order_links.extend(order_links);
+
+        if let Some(depth) = self.depth_cache.as_ref().map(|cache| cache.depth()) {
+            self.set_depth_cache(depth);
+        }
+
+        self.validate_cache()
+            .map_err(SnapshotError::InconsistentBook)?;
+
+        Ok(next_sequence)
+    }
+
+    fn snapshot_levels<P: Ord + Clone + Display>(
+        &self,
+        encoder: &mut Encoder,
+        book_orders: &OrderMap<P>,
+        to_price: impl Fn(&P) -> Price,
+    ) {
+        encoder.put_u32(book_orders.len() as u32);
+        for (key, orders) in book_orders.orders() {
+            encoder.put_u64(to_price(key));
+            encoder.put_u32(orders.len());
+            for item in orders.iter() {
+                self.order_allocator[item.slab_idx() as usize].encode(encoder);
+            }
+        }
+    }
+
+    // Orders of a side in queue order, along with the price of their level
+    fn decode_levels(decoder: &mut Decoder) -> Result<Vec<(Price, T)>, SnapshotError> {
+        let mut orders = Vec::new();
+        let level_count = decoder.get_u32()?;
+        for _ in 0..level_count {
+            let price = decoder.get_u64()?;
+            let order_count = decoder.get_u32()?;
+            for _ in 0..order_count {
+                orders.push((price, T::decode(decoder)?));
+            }
+        }
+
+        Ok(orders)
+    }
+
+    // Every order sits at the level of its price and only once, checked before the book is cleared
+    fn check_levels(levels: &[Vec<(Price, T)>]) -> Result<(), SnapshotError> {
+        let mut order_ids = IdHashSet::default();
+        for (price, order) in levels.iter().flatten() {
+            let order_price = if order.is_stop() {
+                order.stop_price()
+            } else {
+                order.price()
+            };
+            if order_price != *price {
+                return Err(SnapshotError::InconsistentBook(format!(
+                    "Order {} priced {} at level {}",
+                    order.id(),
+                    order_price,
+                    price
+                )));
+            }
+
+            if !order_ids.insert(order.id()) {
+                return Err(OrderError::DuplicateOrderId.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    // Put the order back at the back of its level, as it was when it rested
+    fn restore_order(&mut self, price: Price, order: T) {
+        let (order_id, quantity) = (order.id(), order.quantity());
+        let order_idx = self.order_allocator.insert(order) as SlabIndex;
+        let order = &self.order_allocator[order_idx as usize];
+        let position = match (order.is_stop(), order.is_buy()) {
+            (false, true) => {
+                self.bids
+                    .add_order(&ReverseOrd::new(price), order_idx, order_id, quantity)
+            }
+            (false, false) => self.asks.add_order(&price, order_idx, order_id, quantity),
+            (true, true) => {
+                self.stop_bids
+                    .add_order(&ReverseOrd::new(price), order_idx, order_id, quantity)
+            }
+            (true, false) => self
+                .stop_asks
+                .add_order(&price, order_idx, order_id, quantity),
+        };
+        self.order_index
+            .insert(order_id, OrderLocation::new(order_idx, position));
+
+        // Trailed from an unknown market price, checked again on the next market move
+        if order.order_type().is_trailing_stop() {
+            let market_price = if order.is_buy() { Price::MAX } else { 0 };
+            self.trailing_stops
+                .insert(order_id, order.order_side(), market_price);
+        }
+        if !order.is_stop() && order.is_all_or_none() {
+            if order.is_buy() {
+                self.aon_bids += 1;
+            } else {
+                self.aon_asks += 1;
+            }
+        }
+        if order.is_expirable() && order.expire_time() > 0 {
+            self.expiry_scheduler
+                .schedule(order.expire_time(), order_id);
+        }
+    }
+
+    // Drop every order, keeping the settings of the book
+    fn clear_book(&mut self) {
+        self.order_allocator.clear();
+        self.bids = OrderMap::new();
+        self.asks = OrderMap::new();
+        self.stop_bids = OrderMap::new();
+        self.stop_asks = OrderMap::new();
+        self.trailing_stops.clear();
+        self.order_index.clear();
+        self.aon_bids = 0;
+        self.aon_asks = 0;
+        self.order_links.clear();
+        self.linked_fills.clear();
+        self.expiry_scheduler.clear();
+        self.events.clear();
+        if let Some(depth_feed) = &mut self.depth_feed {
+            depth_feed.clear();
+        }
+    }
+}
+
+// Implementation of the `OrderBook` struct, for managing bids and asks
+impl<T: Order, L: OrderListener, C: Clock> OrderBook<T, L, C> {
+    fn place_order(&mut self, order: &T, is_replace: bool) -> Result<Vec<OrderMatch>, OrderError> {
+        // Order id is the key to find the resting order
+        if self.order_index.contains_key(&order.id()) {
+            return Err(OrderError::DuplicateOrderId);
+        }
+
+        // Post-only order should never take liquidity
+        if order.is_limit_price()
+            && !order.post_only().is_none()
+            && let Some(price) = self.post_only_price(order)?
+        {
+            return self.place_order(&order.clone().with_price(price), is_replace);
+        }
+
+        // Day order without an expire time lives until the end of the trading day
+        if order.time_in_force() == TimeInForce::DAY && order.expire_time() == 0 && self.day_end > 0
+        {
+            return self.place_order(&order.clone().with_expire_time(self.day_end), is_replace);
+        }
+
+        if is_replace {
+            self.events.emit(OrderEvent::Replaced {
+                order_id: order.id(),
+                price: order.price(),
+                quantity: order.quantity(),
+            });
+        } else {
+            self.events.emit(OrderEvent::Accepted {
+                order_id: order.id(),
+            });
+        }
+
+        // Park stop order until its stop price is hit
+        if order.is_stop() && !self.is_stop_triggered(order.order_side(), self.stop_price(order)) {
+            self.insert_stop_order(order);
+            return Ok(Vec::new());
+        }
+
+        // Using slab allocator for performance
+        let order_idx = self.order_allocator.insert(order.clone());
+        self.order_allocator[order_idx].set_accept_time(self.transact_time);
+        let mut order_matches: Vec<OrderMatch> = Vec::new();
+
+        // Stop order already hit at arrival is activated right away
+        if order.is_stop() {
+            let order = self.activate_stop_order(order_idx);
+            self.execute_order(order_idx, &order, &mut order_matches);
+        } else {
+            self.execute_order(order_idx, order, &mut order_matches);
+        }
+
+        // Trades may hit resting stop orders
+        if !order_matches.is_empty() {
+            self.release_stop_orders(&mut order_matches);
+        }
+
+        return Ok(order_matches);
+    }
+
+    fn release_stop_orders(&mut self, order_matches: &mut Vec<OrderMatch>) {
+        loop {
+            // Trailing stop orders follow the market before being checked
+            self.update_trailing_stops();
+
+            let Some(order_idx) = self.pop_triggered_stop_order() else {
+                break;
+            };
+
+            let order = self.activate_stop_order(order_idx);
+            self.execute_order(order_idx, &order, order_matches);
+        }
+    }
+
+    #[inline(always)]
+    fn reject_on_error<R>(
+        &mut self,
+        order_id: OrderId,
+        result: Result<R, OrderError>,
+    ) -> Result<R, OrderError> {
+        if let Err(reason) = &result {
+            self.events.emit(OrderEvent::Rejected {
+                order_id,
+                reason: reason.clone(),
+            });
+        }
+
+        result
+    }
+
+    fn execute_order(&mut self, order_idx: usize, order: &T, order_matches: &mut Vec<OrderMatch>) {
+        // Market order with protection becomes a limit order at the protection price
+        let protected_order;
+        let order = if order.is_market() && !order.market_protection().is_none() {
+            match self.protect_market_order(order_idx, order) {
+                Some(limit_order) => {
+                    protected_order = limit_order;
+                    &protected_order
+                }
+                None => order,
+            }
+        } else {
+            order
+        };
+
+        // Check if matched
+        self.process_order(order_idx, order, order_matches);
+
+        // Update Book Order, market orders have no price to rest at
+        let mut is_removed = false;
+        if order.is_ephemeral_order() || order.is_market() || !self.update_book_order(order_idx) {
+            let order = self.order_allocator.remove(order_idx);
+            is_removed = true;
+
+            // Immediate and market orders never rest at the book
+            if order.quantity() > 0 {
+                self.events.emit(OrderEvent::Cancelled {
+                    order_id: order.id(),
+                    quantity: order.quantity(),
+                    reason: if order.is_fill_or_kill() {
+                        CancelReason::FillOrKill
+                    } else if order.is_immediate_or_cancel() {
+                        CancelReason::ImmediateOrCancel
+                    } else {
+                        CancelReason::NoLiquidity
+                    },
+                });
+            }
+        } else if (order.is_buy() && self.aon_asks > 0) || (order.is_sell() && self.aon_bids > 0) {
+            // New liquidity may complete All-Or-None orders at the other side
+            self.match_aon_orders(order.order_side(), order.price(), order_matches);
+        }
+
+        if !self.linked_fills.is_empty() {
+            self.process_linked_fills();
+        }
+
+        // Order that never rests leaves the other leg on its own
+        if is_removed && !self.order_links.is_empty() {
+            self.unlink_order(order.id());
+        }
+    }
+
+    // Limit order at the protection price away from the best price at arrival,
+    // None when there is nothing to take and the market order is cancelled
+    fn protect_market_order(&mut self, order_idx: usize, order: &T) -> Option<T> {
+        let best_price = *self.peek_top_price(order.is_sell())?;
+        let protection_price = order.market_protection().protection_price(
+            order.order_side(),
+            best_price,
+            self.instrument_spec.tick_size,
+        );
+        let price = protection_price
+            .max(self.instrument_spec.min_price)
+            .min(self.instrument_spec.max_price);
+
+        let slab_order = &mut self.order_allocator[order_idx];
+        slab_order.set_order_type(OrderType::Limit);
+        slab_order.set_price(price);
+
+        Some(slab_order.clone())
     }
 
-    #[inline(always)]
-    pub fn bids(&self) -> &OrderMap<ReverseOrd<Price>> {
-        return &self.bids;
+    // Cancel or reduce the other leg of the filled One-Cancels-Other orders
+    fn process_linked_fills(&mut self) {
+        let mut linked_fills = mem::take(&mut self.linked_fills);
+
+        for &(order_id, quantity) in linked_fills.iter() {
+            let Some(order_link) = self.order_links.get(&order_id).copied() else {
+                continue;
+            };
+
+            let linked_id = order_link.linked_id();
+            if let Some(linked_idx) = self.linked_order_idx(linked_id) {
+                let linked_quantity = self.order_allocator[linked_idx].quantity();
+
+                if order_link.mode() == OcoMode::Reduce && quantity < linked_quantity {
+                    self.reduce_book_order(linked_idx, quantity);
+                    self.events.emit(OrderEvent::Reduced {
+                        order_id: linked_id,
+                        quantity: linked_quantity - quantity,
+                    });
+                } else {
+                    self.remove_book_order(linked_idx);
+                    self.unlink_order(order_id);
+                    self.events.emit(OrderEvent::Cancelled {
+                        order_id: linked_id,
+                        quantity: linked_quantity,
+                        reason: CancelReason::OneCancelsOther,
+                    });
+                }
+            }
+
+            // Fully filled leg is gone from the book, a reduced leg stays on its own
+            if self.linked_order_idx(order_id).is_none() {
+                self.unlink_order(order_id);
+            }
+        }
+
+        // Keep the allocation for the next fills
+        linked_fills.clear();
+        self.linked_fills = linked_fills;
     }
 
+    // Slab index of the linked order, if it's still at the book
     #[inline(always)]
-    pub fn stop_asks(&self) -> &OrderMap<Price> {
-        return &self.stop_asks;
+    fn linked_order_idx(&self, order_id: OrderId) -> Option<usize> {
+        self.order_index
+            .get(&order_id)
+            .map(|location| location.slab_idx() as usize)
     }
 
     #[inline(always)]
-    pub fn stop_bids(&self) -> &OrderMap<ReverseOrd<Price>> {
-        return &self.stop_bids;
+    fn unlink_order(&mut self, order_id: OrderId) {
+        if let Some(order_link) = self.order_links.remove(&order_id) {
+            self.order_links.remove(&order_link.linked_id());
+        }
     }
 
-    #[inline(always)]
-    pub fn order_allocator(&self) -> &Slab<T> {
-        return &self.order_allocator;
+    // Remove the order resting at bids, asks or stop orders
+    fn remove_book_order(&mut self, order_idx: usize) -> T {
+        let order = self.order_allocator.remove(order_idx);
+        let position = self.order_index.remove(&order.id()).unwrap().position();
+
+        if order.is_stop() {
+            self.trailing_stops.remove(order.id());
+            if order.is_buy() {
+                self.stop_bids.remove_order(
+                    &ReverseOrd::new(order.stop_price()),
+                    position,
+                    order.quantity(),
+                );
+            } else {
+                self.stop_asks
+                    .remove_order(&order.stop_price(), position, order.quantity());
+            }
+        } else if order.is_buy() {
+            self.bids
+                .remove_order(&ReverseOrd::new(order.price()), position, order.quantity());
+        } else {
+            self.asks
+                .remove_order(&order.price(), position, order.quantity());
+        }
+
+        self.decrease_aon_orders(&order);
+        if !order.is_stop() {
+            self.book_changed(order.order_side(), order.price(), false);
+        }
+
+        order
     }
 
-    pub fn insert_order(&mut self, order: &T) -> Option<Vec<OrderMatch>> {
-        // Using slab allocator for performance
-        let order_idx = self.order_allocator.insert(order.clone());
+    // Reduce the order resting at bids, asks or stop orders, keeping its priority
+    fn reduce_book_order(&mut self, order_idx: usize, quantity: Quantity) {
+        let order = &mut self.order_allocator[order_idx];
+        order.set_quantity(order.quantity() - quantity);
 
-        // Check if matched
-        let order_matches = self.process_order(order_idx, order);
+        if order.is_stop() {
+            if order.is_buy() {
+                self.stop_bids
+                    .reduce_quantity(&ReverseOrd::new(order.stop_price()), quantity);
+            } else {
+                self.stop_asks
+                    .reduce_quantity(&order.stop_price(), quantity);
+            }
+        } else if order.is_buy() {
+            self.bids
+                .reduce_quantity(&ReverseOrd::new(order.price()), quantity);
+        } else {
+            self.asks.reduce_quantity(&order.price(), quantity);
+        }
 
-        // Update Book Order
-        if order.is_ephemeral_order() {
-            self.order_allocator.remove(order_idx);
-        } else if !self.update_book_order(order_idx) {
-            self.order_allocator.remove(order_idx);
+        if !order.is_stop() {
+            let (order_side, price) = (order.order_side(), order.price());
+            self.book_changed(order_side, price, false);
         }
+    }
 
-        // Do something with order match
-        if order_matches.is_empty() {
-            return None;
+    fn process_order(&mut self, order_idx: usize, order: &T, order_matches: &mut Vec<OrderMatch>) {
+        // Check if FOK, AON or market
+        // return early if not match quantity
+        // let order = self.order_allocator.get(order_idx).unwrap();
+        if (order.is_fill_or_kill() || order.is_all_or_none())
+            && !self.has_sufficient_quantity(order)
+        {
+            return;
         }
 
-        return Some(order_matches);
+        // Match Order, level by level since All-Or-None orders can be left behind
+        let mut top_price = self.peek_top_price(order.is_sell()).copied();
+        while let Some(price) = top_price {
+            if self.match_order(order_idx, price, order_matches).is_none() {
+                break;
+            }
+
+            top_price = self.next_price(order.is_sell(), price);
+        }
     }
 
-    pub fn replace_order(
+    // Match resting All-Or-None orders crossing the price of the new liquidity
+    fn match_aon_orders(
         &mut self,
-        order: &T,
-        quantity_delta: i64,
-        new_price: Price,
-    ) -> Result<Vec<OrderMatch>, OrderError> {
-        // Only quantity is changing
-        let orders = self.get_orders(order).ok_or(OrderError::OrdersNotFound)?;
-
-        // Get Order Meta
-        // If already found, we should expect it's also exist at slab allocator
-        let (order_index, order_meta) = orders
-            .items()
-            .iter()
-            .enumerate()
-            .find(|(_, item)| item.order_id() == order.id())
-            .ok_or(OrderError::OrderNotFound)?;
+        order_side: OrderSide,
+        price: Price,
+        order_matches: &mut Vec<OrderMatch>,
+    ) {
+        let aon_orders: Vec<usize> = if order_side.is_buy() {
+            Self::collect_aon_orders(&self.asks, &self.order_allocator, |key| *key <= price)
+        } else {
+            Self::collect_aon_orders(&self.bids, &self.order_allocator, |key| key.0 >= price)
+        };
 
-        // Get Order inside Slab Allocator
-        let slab_order = self
-            .order_allocator
-            .get(order_meta.slab_idx() as usize)
-            .ok_or(OrderError::SlabOrderNotFound)?;
+        for aon_idx in aon_orders {
+            let aon_order = self.order_allocator[aon_idx].clone();
+            if !self.has_sufficient_quantity(&aon_order) {
+                continue;
+            }
 
-        // Check if quantity is still valid
-        // New Order Quanttity
-        let new_quantity = {
-            if quantity_delta.is_positive() {
-                slab_order
-                    .quantity()
-                    .saturating_add(quantity_delta as Quantity)
+            // Take it out of the book and match it as incoming order
+            let position = self.order_index.remove(&aon_order.id()).unwrap().position();
+            if aon_order.is_buy() {
+                self.bids.remove_order(
+                    &ReverseOrd::new(aon_order.price()),
+                    position,
+                    aon_order.quantity(),
+                );
+                self.aon_bids -= 1;
             } else {
-                slab_order
-                    .quantity()
-                    .saturating_sub(quantity_delta as Quantity)
+                self.asks
+                    .remove_order(&aon_order.price(), position, aon_order.quantity());
+                self.aon_asks -= 1;
             }
-        };
+            self.book_changed(aon_order.order_side(), aon_order.price(), false);
 
-        if new_quantity == 0 {
-            return Err(OrderError::OrderAlreadyFilled);
+            self.process_order(aon_idx, &aon_order, order_matches);
+            self.order_allocator.remove(aon_idx);
         }
+    }
 
-        // New Order Quantity
-        let new_orders_quantity = orders.orders_quantity() - slab_order.quantity();
+    #[inline(always)]
+    fn collect_aon_orders<P: Ord + Clone + Display>(
+        book_orders: &OrderMap<P>,
+        order_allocator: &Slab<T>,
+        is_match_price: impl Fn(&P) -> bool,
+    ) -> Vec<usize> {
+        book_orders
+            .orders()
+            .iter()
+            .take_while(|(key, _)| is_match_price(key))
+            .flat_map(|(_, orders)| orders.iter())
+            .map(|item| item.slab_idx() as usize)
+            .filter(|&slab_idx| order_allocator[slab_idx].is_all_or_none())
+            .collect()
+    }
 
-        // New Total Quantity
-        let new_total_quantity = self.total_quantity(order.is_buy()) - slab_order.quantity();
+    // Quantity the order would get by walking the book, skipping All-Or-None
+    // orders which can't be filled completely and orders of the same owner
+    // under self-trade prevention, same as `match_order` does
+    #[inline(always)]
+    fn fillable_quantity<P: Ord + Clone + Display>(
+        book_orders: &OrderMap<P>,
+        order_allocator: &Slab<T>,
+        is_match_price: impl Fn(&P) -> bool,
+        order: &T,
+    ) -> Quantity {
+        let quantity = order.quantity();
+        let mut remaining_quantity = quantity;
 
-        // Create New Order
-        let mut new_order = order.clone().with_quantity(new_quantity);
-        if new_price != 0 {
-            new_order = new_order.with_price(new_price);
-        }
+        for (key, orders) in book_orders.orders().iter() {
+            if !is_match_price(key) {
+                break;
+            }
 
-        // Delete last order
-        // delete from slab allocator
-        self.order_allocator.remove(order_meta.slab_idx() as usize);
+            for item in orders.iter() {
+                let book_order = &order_allocator[item.slab_idx() as usize];
+                if book_order.is_all_or_none() && book_order.quantity() > remaining_quantity {
+                    continue;
+                }
 
-        // delete from btreemap
-        let orders = self.get_orders_mut(order).unwrap();
-        orders.items_mut().remove(order_index);
+                // Cancelled resting orders are no liquidity, any other mode
+                // cancels or decrements the incoming order before it fills
+                if book_order.owner_id() == order.owner_id()
+                    && !order.self_trade_prevention().is_none()
+                {
+                    if order.self_trade_prevention() == SelfTradePrevention::CancelOldest {
+                        continue;
+                    }
 
-        // Change Orders Quantity
-        orders.set_orders_quantity(new_orders_quantity);
+                    return quantity - remaining_quantity;
+                }
 
-        // Change Total Quantity
-        self.decrease_total_quantity(order.is_buy(), new_total_quantity);
+                remaining_quantity -= cmp::min(book_order.quantity(), remaining_quantity);
+                if remaining_quantity == 0 {
+                    return quantity;
+                }
+            }
+        }
 
-        // Insert as new order
-        let matches = self.insert_order(&new_order).unwrap_or_default();
-        Ok(matches)
+        quantity - remaining_quantity
     }
 
-    pub fn cancel_order(&mut self, order: &T) -> Result<T, OrderError> {
-        // Get immutable orders
-        let orders = self.get_orders(order).ok_or(OrderError::OrdersNotFound)?;
-
-        // Get Order Index and Order Meta from orders
-        let (order_index, order_meta) = orders
-            .items()
-            .iter()
-            .enumerate()
-            .find(|(_, item)| item.order_id() == order.id())
-            .ok_or(OrderError::OrderNotFound)?;
+    // Price for the post-only order crossing the spread, rejected or slid
+    // one tick away from the best price at the other side
+    #[inline(always)]
+    fn post_only_price(&self, order: &T) -> Result<Option<Price>, OrderError> {
+        let Some(&top_price) = self.peek_top_price(order.is_sell()) else {
+            return Ok(None);
+        };
 
-        // Get Slab Order
-        let slab_order = self
-            .order_allocator
-            .try_remove(order_meta.slab_idx() as usize)
-            .ok_or(OrderError::SlabFailedRemoveOrder)?;
+        if !self.is_match_price(&order.order_side(), order.price(), top_price) {
+            return Ok(None);
+        }
 
-        // Get mutable orders
-        let orders = self.get_orders_mut(&slab_order).unwrap();
+        let slide_price = if order.is_buy() {
+            top_price
+                .checked_sub(self.instrument_spec.tick_size)
+                .filter(|price| *price > 0)
+        } else {
+            top_price.checked_add(self.instrument_spec.tick_size)
+        };
 
-        // Remove Order at orders
-        orders.items_mut().remove(order_index);
+        match (order.post_only(), slide_price) {
+            (PostOnly::Slide, Some(price)) => {
+                // Slid price must still be valid for the instrument
+                self.instrument_spec.validate_price(price)?;
+                self.instrument_spec
+                    .validate_notional(price, order.quantity())?;
+                Ok(Some(price))
+            }
+            _ => Err(OrderError::PostOnlyWouldCross),
+        }
+    }
 
-        // Set Orders Quantity
-        orders.set_orders_quantity(orders.orders_quantity() - slab_order.quantity());
+    // Stop price of the incoming stop order, trailing stop order takes it from the market price
+    #[inline(always)]
+    fn stop_price(&self, order: &T) -> Price {
+        let Some(trailing_offset) = order.trailing_offset() else {
+            return order.stop_price();
+        };
 
-        // Check if no order leave at orders
-        if orders.len() == 0 {
-            self.remove_orders(slab_order.is_buy(), &order.price());
+        // Not armed until the first trade
+        if self.current_market_price == 0 {
+            return if order.is_buy() { Price::MAX } else { 0 };
         }
 
-        // Decrease Total Quantity
-        self.decrease_total_quantity(slab_order.is_buy(), slab_order.quantity());
-
-        return Ok(slab_order);
+        trailing_offset.stop_price(order.order_side(), self.current_market_price)
     }
 
-    /// Insert a stop order into the order book.
-    /// Stop orders are stored in stop_bids or stop_asks depending on side.
-    /// Returns true if the stop order was added.
-    pub fn insert_stop_order(&mut self, order: &T) {
-        let order_idx = self.order_allocator.insert(order.clone());
+    // Ratchet trailing stop orders toward the market price, sell stop orders
+    // only move up on rising market and buy stop orders only move down on falling market
+    fn update_trailing_stops(&mut self) {
+        let market_price = self.current_market_price;
+        let last_market_price = self.trailing_market_price;
+        if market_price == last_market_price {
+            return;
+        }
 
-        // Add to stop order map
-        if order.is_buy() {
-            let key = &ReverseOrd::new(order.price());
-            self.stop_bids
-                .add_order(key, order_idx as SlabIndex, order.id(), order.quantity());
-        } else {
-            let key = &order.price();
-            self.stop_asks
-                .add_order(key, order_idx as SlabIndex, order.id(), order.quantity());
+        self.trailing_market_price = market_price;
+        if self.trailing_stops.is_empty() {
+            return;
+        }
+
+        for order_id in self.trailing_stops.pop_moving(market_price) {
+            let location = self.order_index.get_mut(&order_id).unwrap();
+            let order_idx = location.slab_idx();
+            let order = &mut self.order_allocator[order_idx as usize];
+            let last_stop_price = order.stop_price();
+            let stop_price = order
+                .trailing_offset()
+                .unwrap()
+                .stop_price(order.order_side(), market_price);
+
+            // Moved to the back of the new stop price level
+            if order.is_buy() && stop_price < last_stop_price {
+                order.set_stop_price(stop_price);
+                self.stop_bids.remove_order(
+                    &ReverseOrd::new(last_stop_price),
+                    location.position(),
+                    order.quantity(),
+                );
+                let position = self.stop_bids.add_order(
+                    &ReverseOrd::new(stop_price),
+                    order_idx,
+                    order.id(),
+                    order.quantity(),
+                );
+                *location = OrderLocation::new(order_idx, position);
+            } else if order.is_sell() && stop_price > last_stop_price {
+                order.set_stop_price(stop_price);
+                self.stop_asks.remove_order(
+                    &last_stop_price,
+                    location.position(),
+                    order.quantity(),
+                );
+                let position =
+                    self.stop_asks
+                        .add_order(&stop_price, order_idx, order.id(), order.quantity());
+                *location = OrderLocation::new(order_idx, position);
+            }
         }
     }
 
-    pub fn recover_order_price(&self, order_side: OrderSide, order_id: OrderId) -> Option<Price> {
-        if order_side.is_sell() {
-            return self.asks.orders().iter().find_map(|(price, orders)| {
-                for order in orders.items() {
-                    if order.order_id() == order_id {
-                        return Some(*price);
-                    }
-                }
+    #[inline(always)]
+    fn is_stop_triggered(&self, order_side: OrderSide, stop_price: Price) -> bool {
+        // No trade has happened yet
+        if self.current_market_price == 0 {
+            return false;
+        }
 
-                None
-            });
+        if order_side.is_buy() {
+            self.current_market_price >= stop_price
         } else {
-            return self.bids.orders().iter().find_map(|(price, orders)| {
-                for order in orders.items() {
-                    if order.order_id() == order_id {
-                        return Some(price.0);
-                    }
-                }
-
-                None
-            });
+            self.current_market_price <= stop_price
         }
     }
 
-    /// Trigger stop orders if the market price crosses their stop price.
-    /// This should be called after each trade or price update.
-    // pub fn trigger_stop_orders(&mut self) -> Vec<OrderMatch> {
-    //     let mut triggered_matches = Vec::new();
-
-    //     // Collect Bids Price
-    //     let bids_prices = self
-    //         .bids
-    //         .collect_until_key(|key| self.current_market_price < key.0)
-    //         .iter()
-    //         .map(|item| item.0);
+    fn pop_triggered_stop_order(&mut self) -> Option<usize> {
+        let order_idx = if let Some(key) = self.stop_bids.peek_back_key().copied()
+            && self.is_stop_triggered(OrderSide::Buy, key.0)
+        {
+            // Buy stop with the lowest stop price is hit first
+            Self::pop_stop_order(&mut self.stop_bids, &key, &self.order_allocator)
+        } else if let Some(key) = self.stop_asks.peek_back_key().copied()
+            && self.is_stop_triggered(OrderSide::Sell, key)
+        {
+            // Sell stop with the highest stop price is hit first
+            Self::pop_stop_order(&mut self.stop_asks, &key, &self.order_allocator)
+        } else {
+            return None;
+        };
 
-    //     triggered_matches
-    // }
+        // No longer resting until it's activated and rests again
+        self.order_index
+            .remove(&self.order_allocator[order_idx].id());
+        Some(order_idx)
+    }
 
-    // Optional: Validate cache consistency
     #[inline(always)]
-    pub fn validate_cache(&self) -> Result<(), String> {
-        self.asks.validate_cache()?;
-        self.bids.validate_cache()?;
+    fn pop_stop_order<P: Ord + Clone + Display>(
+        stop_orders: &mut OrderMap<P>,
+        key: &P,
+        order_allocator: &Slab<T>,
+    ) -> usize {
+        let orders = stop_orders.get_orders_mut(key).unwrap();
+        let order_meta = orders.pop_front().unwrap();
+        let quantity = order_allocator[order_meta.slab_idx() as usize].quantity();
+
+        orders.set_orders_quantity(orders.orders_quantity() - quantity);
+        if orders.len() == 0 {
+            stop_orders.remove_orders(key);
+        }
 
-        return Ok(());
+        stop_orders.set_total_quantity(stop_orders.total_quantity() - quantity);
+        order_meta.slab_idx() as usize
     }
-}
-
-// Implementation of the `OrderBook` struct, for managing bids and asks
-impl<T: Order> OrderBook<T> {
-    fn process_order(&mut self, order_idx: usize, order: &T) -> Vec<OrderMatch> {
-        let mut order_matches: Vec<OrderMatch> = Vec::new();
 
-        // Check if FOK or market
-        // return early if not match quantity
-        // let order = self.order_allocator.get(order_idx).unwrap();
-        if order.is_fill_or_kill() && !self.has_sufficient_quantity(order) {
-            return order_matches;
+    // Convert the triggered stop order into a market or limit order
+    #[inline(always)]
+    fn activate_stop_order(&mut self, order_idx: usize) -> T {
+        let order = self.order_allocator.get_mut(order_idx).unwrap();
+        if order.order_type().is_trailing_stop() {
+            self.trailing_stops.remove(order.id());
         }
 
-        // Match Order
-        loop {
-            let top_price = self.peek_top_price(order.is_sell());
-            if top_price == None {
-                break;
-            }
-
-            if self.match_order(order_idx, *top_price.unwrap(), &mut order_matches) == None {
-                break;
-            }
+        if order.order_type().is_stop_limit() {
+            order.set_order_type(OrderType::Limit);
+        } else {
+            order.set_order_type(OrderType::Market);
         }
 
-        // Return order match
-        return order_matches;
+        order.clone()
     }
 
     fn match_order(
@@ -335,12 +1532,13 @@ impl<T: Order> OrderBook<T> {
             }
         };
 
-        // Set Order and total quantity
-        let min_total_quantity = cmp::min(orders.orders_quantity(), order_quantity);
-        orders.set_orders_quantity(orders.orders_quantity() - min_total_quantity);
+        let mut matched_quantity: Quantity = 0;
+        let mut prevented_quantity: Quantity = 0;
+        let mut prevented_ids: Vec<OrderId> = Vec::new();
+        let mut position = 0;
 
-        while orders.len() > 0 {
-            let front_order_meta = orders.items().front().unwrap();
+        while let Some((front_position, &front_order_meta)) = orders.get_from(position) {
+            position = front_position;
 
             assert!(
                 order_quantity > 0,
@@ -357,23 +1555,123 @@ impl<T: Order> OrderBook<T> {
                 .get2_mut(front_order_meta.slab_idx() as usize, order_idx)
                 .unwrap();
 
-            // Match the order with the front order
-            let min_quantity = cmp::min(front_order.quantity(), order_quantity);
-            front_order.set_quantity(front_order.quantity() - min_quantity);
-            order_quantity -= min_quantity;
-
-            // Add the match result to the order matches
-            order_matches.push(OrderMatch {
-                order_side: order.order_side(),
-                price: top_price,
-                quantity: min_quantity,
-                match_from_id: order.id(),
-                match_to_id: front_order.id(),
-            });
+            // All-Or-None order at the book can only be filled completely,
+            // leave it behind without blocking the orders after it
+            if front_order.is_all_or_none() && front_order.quantity() > order_quantity {
+                position += 1;
+                continue;
+            }
+
+            if order.owner_id() == front_order.owner_id()
+                && !order.self_trade_prevention().is_none()
+            {
+                // Orders of the same owner never trade with each other
+                let (order_decrement, front_decrement) = match order.self_trade_prevention() {
+                    SelfTradePrevention::CancelNewest => (order_quantity, 0),
+                    SelfTradePrevention::CancelOldest => (0, front_order.quantity()),
+                    SelfTradePrevention::CancelBoth => (order_quantity, front_order.quantity()),
+                    _ => {
+                        let min_quantity = cmp::min(front_order.quantity(), order_quantity);
+                        (min_quantity, min_quantity)
+                    }
+                };
+
+                self.events.emit(OrderEvent::SelfTradePrevented {
+                    order_id: order.id(),
+                    resting_order_id: front_order.id(),
+                });
+
+                if front_decrement > 0 {
+                    front_order.set_quantity(front_order.quantity() - front_decrement);
+                    prevented_quantity += front_decrement;
+                    self.events.emit(Self::prevented_event(
+                        front_order.id(),
+                        front_decrement,
+                        front_order.quantity(),
+                    ));
+                }
+
+                if order_decrement > 0 {
+                    order_quantity -= order_decrement;
+                    self.events.emit(Self::prevented_event(
+                        order.id(),
+                        order_decrement,
+                        order_quantity,
+                    ));
+                }
+
+                // Cancelled orders no longer cancel their linked order
+                if !self.order_links.is_empty() {
+                    if front_order.quantity() == 0 {
+                        prevented_ids.push(front_order.id());
+                    }
+
+                    if order_quantity == 0 {
+                        prevented_ids.push(order.id());
+                    }
+                }
+            } else {
+                // Match the order with the front order
+                let min_quantity = cmp::min(front_order.quantity(), order_quantity);
+                front_order.set_quantity(front_order.quantity() - min_quantity);
+                order_quantity -= min_quantity;
+                matched_quantity += min_quantity;
+
+                // Add the match result to the order matches
+                order_matches.push(OrderMatch {
+                    order_side: order.order_side(),
+                    price: top_price,
+                    quantity: min_quantity,
+                    match_from_id: order.id(),
+                    match_to_id: front_order.id(),
+                    timestamp: self.transact_time,
+                });
+
+                self.events.emit(OrderEvent::Fill {
+                    order_id: order.id(),
+                    contra_order_id: front_order.id(),
+                    price: top_price,
+                    quantity: min_quantity,
+                    leaves_quantity: order_quantity,
+                    role: LiquidityRole::Taker,
+                });
+                self.events.emit(OrderEvent::Fill {
+                    order_id: front_order.id(),
+                    contra_order_id: order.id(),
+                    price: top_price,
+                    quantity: min_quantity,
+                    leaves_quantity: front_order.quantity(),
+                    role: LiquidityRole::Maker,
+                });
+
+                // Keep track of the last trade
+                self.current_market_price = top_price;
+                self.last_trade_quantity = min_quantity;
+
+                // Other leg of One-Cancels-Other orders is handled after matching
+                if !self.order_links.is_empty() {
+                    if self.order_links.contains_key(&order.id()) {
+                        self.linked_fills.push((order.id(), min_quantity));
+                    }
+
+                    if self.order_links.contains_key(&front_order.id()) {
+                        self.linked_fills.push((front_order.id(), min_quantity));
+                    }
+                }
+            }
 
             // If the front order is fully matched, remove it from the queue
             if front_order.quantity() == 0 {
-                let order_meta = orders.pop_front().unwrap();
+                if front_order.is_all_or_none() {
+                    if order_side.is_buy() {
+                        self.aon_asks -= 1;
+                    } else {
+                        self.aon_bids -= 1;
+                    }
+                }
+
+                let order_meta = orders.remove(position).unwrap();
+                self.order_index.remove(&order_meta.order_id());
 
                 // Remove the order from the allocator
                 self.order_allocator.remove(order_meta.slab_idx() as usize);
@@ -383,14 +1681,26 @@ impl<T: Order> OrderBook<T> {
             if order_quantity == 0 {
                 break;
             }
+
+            position += 1;
         }
 
         // Remove the order from the book if it has no remaining quantity
-        if orders.orders_quantity() == 0 {
+        let removed_quantity = matched_quantity + prevented_quantity;
+        orders.set_orders_quantity(orders.orders_quantity() - removed_quantity);
+        if orders.len() == 0 {
             self.remove_orders(order_side.is_sell(), &top_price);
         }
 
-        self.decrease_total_quantity(order_side.is_sell(), min_total_quantity);
+        if removed_quantity > 0 {
+            self.book_changed(order_side.opposite(), top_price, false);
+        }
+
+        self.decrease_total_quantity(order_side.is_sell(), removed_quantity);
+        for order_id in prevented_ids {
+            self.unlink_order(order_id);
+        }
+
         self.order_allocator
             .get_mut(order_idx)
             .unwrap()
@@ -402,35 +1712,66 @@ impl<T: Order> OrderBook<T> {
         Some(())
     }
 
+    // Cancelled once nothing is left, otherwise reduced to the leaves quantity
     #[inline(always)]
-    fn get_orders(&self, order: &T) -> Option<&Orders> {
-        if order.is_buy() {
-            return self.bids.get_orders(&ReverseOrd::new(order.price()));
+    fn prevented_event(order_id: OrderId, decrement: Quantity, quantity: Quantity) -> OrderEvent {
+        if quantity == 0 {
+            OrderEvent::Cancelled {
+                order_id,
+                quantity: decrement,
+                reason: CancelReason::SelfTradePrevention,
+            }
         } else {
-            return self.asks.get_orders(&order.price());
+            OrderEvent::Reduced { order_id, quantity }
         }
     }
 
     #[inline(always)]
-    fn get_orders_mut(&mut self, order: &T) -> Option<&mut Orders> {
-        if order.is_buy() {
-            return self.bids.get_orders_mut(&ReverseOrd::new(order.price()));
+    pub fn peek_top_price(&self, is_bids: bool) -> Option<&Price> {
+        if is_bids {
+            return self.bids.peek_key().map(|i| &i.0);
         } else {
-            return self.asks.get_orders_mut(&order.price());
+            return self.asks.peek_key();
         }
     }
 
     #[inline(always)]
-    pub fn peek_top_price(&self, is_bids: bool) -> Option<&Price> {
+    fn next_price(&self, is_bids: bool, price: Price) -> Option<Price> {
         if is_bids {
-            return self.bids.peek_key().map(|i| &i.0);
+            self.bids.next_key(&ReverseOrd::new(price)).map(|i| i.0)
         } else {
-            return self.asks.peek_key();
+            self.asks.next_key(&price).copied()
         }
     }
 
     #[inline(always)]
     pub fn has_sufficient_quantity(&self, order: &T) -> bool {
+        // All-Or-None orders at the book may not be fillable and orders of the same
+        // owner may not trade, walk the book instead
+        if (order.is_buy() && self.aon_asks > 0)
+            || (order.is_sell() && self.aon_bids > 0)
+            || !order.self_trade_prevention().is_none()
+        {
+            let is_market = !order.is_limit_price();
+            let quantity = if order.is_buy() {
+                Self::fillable_quantity(
+                    &self.asks,
+                    &self.order_allocator,
+                    |key| is_market || *key <= order.price(),
+                    order,
+                )
+            } else {
+                Self::fillable_quantity(
+                    &self.bids,
+                    &self.order_allocator,
+                    |key| is_market || key.0 >= order.price(),
+                    order,
+                )
+            };
+
+            return quantity >= order.quantity();
+        }
+
         let quantity: Quantity = {
             if order.is_limit_price() {
                 if order.is_buy() {
@@ -459,15 +1800,6 @@ impl<T: Order> OrderBook<T> {
         return quantity >= order.quantity();
     }
 
-    #[inline(always)]
-    fn total_quantity(&self, is_bids: bool) -> Quantity {
-        if is_bids {
-            return self.bids.total_quantity();
-        } else {
-            return self.asks.total_quantity();
-        }
-    }
-
     #[inline(always)]
     fn set_total_quantity(&mut self, is_bids: bool, new_quantity: Quantity) {
         if is_bids {
@@ -512,19 +1844,173 @@ impl<T: Order> OrderBook<T> {
         }
 
         // Add Order
-        if order.is_buy() {
+        let position = if order.is_buy() {
             let key = &ReverseOrd::new(order.price());
             self.bids
-                .add_order(key, order_idx as SlabIndex, order.id(), order.quantity());
+                .add_order(key, order_idx as SlabIndex, order.id(), order.quantity())
         } else {
             let key = &order.price();
             self.asks
-                .add_order(key, order_idx as SlabIndex, order.id(), order.quantity());
+                .add_order(key, order_idx as SlabIndex, order.id(), order.quantity())
+        };
+
+        self.order_index.insert(
+            order.id(),
+            OrderLocation::new(order_idx as SlabIndex, position),
+        );
+        self.events.emit(OrderEvent::Rested {
+            order_id: order.id(),
+            price: order.price(),
+            quantity: order.quantity(),
+        });
+
+        if order.is_all_or_none() {
+            if order.is_buy() {
+                self.aon_bids += 1;
+            } else {
+                self.aon_asks += 1;
+            }
         }
 
+        let (order_side, price) = (order.order_side(), order.price());
+        if order.is_expirable() && order.expire_time() > 0 {
+            self.expiry_scheduler
+                .schedule(order.expire_time(), order.id());
+        }
+        self.book_changed(order_side, price, true);
+
         return true;
     }
 
+    // Expiring order needs an expire time still ahead of the book time
+    #[inline(always)]
+    fn validate_expire_time(&self, order: &T) -> Result<(), OrderError> {
+        if !order.is_expirable() {
+            return Ok(());
+        }
+
+        let expire_time = if order.expire_time() == 0 && order.time_in_force() == TimeInForce::DAY {
+            // No day end configured, the DAY order stays until cancelled
+            if self.day_end == 0 {
+                return Ok(());
+            }
+
+            self.day_end
+        } else {
+            order.expire_time()
+        };
+        if expire_time == 0 || expire_time <= self.current_time {
+            return Err(OrderError::InvalidExpireTime);
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn clear_events(&mut self) {
+        self.transact_time = self.clock.now();
+        self.events.clear();
+        if let Some(depth_feed) = &mut self.depth_feed {
+            depth_feed.clear();
+        }
+    }
+
+    // Update the depth cache and feed, and notify the listener of what is left at the price level
+    #[inline(always)]
+    fn book_changed(&mut self, order_side: OrderSide, price: Price, is_added: bool) {
+        if !L::ENABLED && self.depth_cache.is_none() && self.depth_feed.is_none() {
+            return;
+        }
+
+        let level = self.price_level(order_side, price);
+        if let Some(depth_feed) = &mut self.depth_feed {
+            depth_feed.push(order_side, level, is_added);
+        }
+
+        if let Some(depth_cache) = &mut self.depth_cache
+            && depth_cache.update(order_side, level)
+        {
+            // Cached level is gone, pull the next one of the book
+            let next_price = depth_cache.last_price(order_side).map_or_else(
+                || self.peek_top_price(order_side.is_buy()).copied(),
+                |last_price| self.next_price(order_side.is_buy(), last_price),
+            );
+            if let Some(next_price) = next_price {
+                let next_level = self.price_level(order_side, next_price);
+                if let Some(depth_cache) = &mut self.depth_cache {
+                    depth_cache.push_level(order_side, next_level);
+                }
+            }
+        }
+
+        self.events.book_changed(order_side, price, level.quantity);
+    }
+
+    #[inline(always)]
+    fn price_level(&self, order_side: OrderSide, price: Price) -> PriceLevel {
+        let orders = if order_side.is_buy() {
+            self.bids.get_orders(&ReverseOrd::new(price))
+        } else {
+            self.asks.get_orders(&price)
+        };
+
+        PriceLevel {
+            price,
+            quantity: orders.map_or(0, |orders| orders.orders_quantity()),
+            order_count: orders.map_or(0, |orders| orders.len() as usize),
+        }
+    }
+
+    #[inline(always)]
+    fn book_orders<'a, P: Ord + Clone + Display>(
+        book_orders: &'a OrderMap<P>,
+        order_allocator: &'a Slab<T>,
+        to_price: impl Fn(&P) -> Price + 'a,
+    ) -> impl Iterator<Item = BookOrder<'a, T>> {
+        book_orders.orders().iter().flat_map(move |(key, orders)| {
+            let price = to_price(key);
+            orders
+                .iter()
+                .enumerate()
+                .map(move |(queue_position, item)| BookOrder {
+                    price,
+                    queue_position,
+                    order: &order_allocator[item.slab_idx() as usize],
+                })
+        })
+    }
+
+    #[inline(always)]
+    fn price_levels<P: Ord + Clone + Display>(
+        book_orders: &OrderMap<P>,
+        depth: usize,
+        to_price: impl Fn(&P) -> Price,
+    ) -> Vec<PriceLevel> {
+        book_orders
+            .orders()
+            .iter()
+            .take(depth)
+            .map(|(key, orders)| PriceLevel {
+                price: to_price(key),
+                quantity: orders.orders_quantity(),
+                order_count: orders.len() as usize,
+            })
+            .collect()
+    }
+
+    #[inline(always)]
+    fn decrease_aon_orders(&mut self, order: &T) {
+        if !order.is_all_or_none() {
+            return;
+        }
+
+        if order.is_buy() {
+            self.aon_bids -= 1;
+        } else {
+            self.aon_asks -= 1;
+        }
+    }
+
     #[inline(always)]
     fn remove_orders(&mut self, is_bids: bool, top_price: &Price) {
         if is_bids {
@@ -535,7 +2021,7 @@ impl<T: Order> OrderBook<T> {
     }
 }
 
-impl<T: Order> std::fmt::Display for OrderBook<T> {
+impl<T: Order, L: OrderListener, C: Clock> std::fmt::Display for OrderBook<T, L, C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         _ = writeln!(
             f,
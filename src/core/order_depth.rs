@@ -0,0 +1,186 @@
+use crate::core::order::{OrderSide, Price, Quantity};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceLevel {
+    pub price: Price,
+    pub quantity: Quantity,
+    pub order_count: usize,
+}
+
+// Resting order of a price level, `queue_position` 0 is the front of the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookOrder<'a, T> {
+    pub price: Price,
+    pub queue_position: usize,
+    pub order: &'a T,
+}
+
+// Aggregated price levels, best price first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookDepth {
+    pub bids: Vec<PriceLevel>,
+    pub asks: Vec<PriceLevel>,
+}
+
+impl BookDepth {
+    #[inline(always)]
+    pub fn levels(&self, order_side: OrderSide) -> &[PriceLevel] {
+        if order_side.is_buy() {
+            &self.bids
+        } else {
+            &self.asks
+        }
+    }
+
+    #[inline(always)]
+    fn levels_mut(&mut self, order_side: OrderSide) -> &mut Vec<PriceLevel> {
+        if order_side.is_buy() {
+            &mut self.bids
+        } else {
+            &mut self.asks
+        }
+    }
+}
+
+/// Top price levels of both sides, kept up to date as the book changes
+#[derive(Debug, Clone)]
+pub struct DepthCache {
+    depth: usize,
+    book_depth: BookDepth,
+}
+
+impl DepthCache {
+    #[inline(always)]
+    pub fn new(book_depth: BookDepth, depth: usize) -> Self {
+        Self { depth, book_depth }
+    }
+
+    #[inline(always)]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    #[inline(always)]
+    pub fn book_depth(&self) -> &BookDepth {
+        &self.book_depth
+    }
+
+    #[inline(always)]
+    pub fn last_price(&self, order_side: OrderSide) -> Option<Price> {
+        self.book_depth
+            .levels(order_side)
+            .last()
+            .map(|level| level.price)
+    }
+
+    // Apply the new state of a price level, quantity 0 once the level is gone.
+    // Returns true when a cached level dropped out and the side needs the next level of the book
+    pub fn update(&mut self, order_side: OrderSide, level: PriceLevel) -> bool {
+        let depth = self.depth;
+        let levels = self.book_depth.levels_mut(order_side);
+
+        // First level which isn't better than the changed one
+        let idx = levels
+            .iter()
+            .position(|cached| {
+                if order_side.is_buy() {
+                    cached.price <= level.price
+                } else {
+                    cached.price >= level.price
+                }
+            })
+            .unwrap_or(levels.len());
+
+        if idx < levels.len() && levels[idx].price == level.price {
+            if level.quantity > 0 {
+                levels[idx] = level;
+                return false;
+            }
+
+            // Side was full, the book may have more levels behind it
+            levels.remove(idx);
+            return levels.len() + 1 == depth;
+        }
+
+        if level.quantity > 0 && idx < depth {
+            levels.insert(idx, level);
+            levels.truncate(depth);
+        }
+
+        false
+    }
+
+    #[inline(always)]
+    pub fn push_level(&mut self, order_side: OrderSide, level: PriceLevel) {
+        self.book_depth.levels_mut(order_side).push(level);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthChangeKind {
+    Added,   // New price level
+    Changed, // Quantity or order count of the level changed
+    Removed, // Nothing left at the level
+}
+
+// New aggregate of a price level at bids or asks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepthChange {
+    pub sequence: u64,
+    pub order_side: OrderSide,
+    pub price: Price,
+    pub quantity: Quantity,
+    pub order_count: usize,
+    pub kind: DepthChangeKind,
+}
+
+/// Level changes of the last operation, numbered so subscribers can detect gaps
+#[derive(Debug, Clone, Default)]
+pub struct DepthFeed {
+    changes: Vec<DepthChange>,
+    sequence: u64,
+}
+
+impl DepthFeed {
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline(always)]
+    pub fn changes(&self) -> &[DepthChange] {
+        &self.changes
+    }
+
+    // Sequence of the last change, carried across operations
+    #[inline(always)]
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.changes.clear();
+    }
+
+    #[inline(always)]
+    pub fn push(&mut self, order_side: OrderSide, level: PriceLevel, is_added: bool) {
+        let kind = if level.quantity == 0 {
+            DepthChangeKind::Removed
+        } else if is_added && level.order_count == 1 {
+            DepthChangeKind::Added
+        } else {
+            DepthChangeKind::Changed
+        };
+
+        self.sequence += 1;
+        self.changes.push(DepthChange {
+            sequence: self.sequence,
+            order_side,
+            price: level.price,
+            quantity: level.quantity,
+            order_count: level.order_count,
+            kind,
+        });
+    }
+}
@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-#[derive(Debug, Error, PartialEq)]
+#[derive(Debug, Clone, Error, PartialEq)]
 pub enum OrderError {
     #[error("Orders not found")]
     OrdersNotFound,
@@ -19,4 +19,61 @@ pub enum OrderError {
 
     #[error("Order already filled")]
     OrderAlreadyFilled,
+
+    #[error("Quantity delta exceeds the order quantity")]
+    InvalidQuantityDelta,
+
+    #[error("Post-only order would take liquidity")]
+    PostOnlyWouldCross,
+
+    #[error("Order already linked")]
+    OrderAlreadyLinked,
+
+    #[error("Linked order already filled")]
+    LinkedOrderFilled,
+
+    #[error("Order id already exists")]
+    DuplicateOrderId,
+
+    #[error("Symbol not found")]
+    SymbolNotFound,
+
+    #[error("Symbol already exists")]
+    DuplicateSymbol,
+
+    #[error("Trading is not open for the symbol")]
+    TradingNotOpen,
+
+    #[error("Quantity must be greater than 0")]
+    InvalidQuantity,
+
+    #[error("Quantity is not a multiple of the lot size")]
+    InvalidLotSize,
+
+    #[error("Quantity is below the minimum quantity")]
+    QuantityBelowMinimum,
+
+    #[error("Quantity is above the maximum quantity")]
+    QuantityAboveMaximum,
+
+    #[error("Price must be greater than 0")]
+    InvalidPrice,
+
+    #[error("Price is not a multiple of the tick size")]
+    InvalidTickSize,
+
+    #[error("Price is below the minimum price")]
+    PriceBelowMinimum,
+
+    #[error("Price is above the maximum price")]
+    PriceAboveMaximum,
+
+    #[error("Notional is below the minimum notional")]
+    NotionalBelowMinimum,
+
+    #[error("Expire time is missing or already passed")]
+    InvalidExpireTime,
+
+    #[error("Stop price of a trailing stop order can not be replaced")]
+    TrailingStopPriceReplace,
 }
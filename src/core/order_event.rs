@@ -0,0 +1,78 @@
+use crate::core::{
+    order::{OrderId, Price, Quantity},
+    order_error::OrderError,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderEvent {
+    // Order passed the validation and entered the book
+    Accepted {
+        order_id: OrderId,
+    },
+
+    // Order rests at bids or asks, quantity is the leaves quantity
+    Rested {
+        order_id: OrderId,
+        price: Price,
+        quantity: Quantity,
+    },
+
+    // Order traded, reported for both the taker and the maker
+    Fill {
+        order_id: OrderId,
+        contra_order_id: OrderId,
+        price: Price,
+        quantity: Quantity,
+        leaves_quantity: Quantity,
+        role: LiquidityRole,
+    },
+
+    // Order removed from the book, quantity is the cancelled quantity
+    Cancelled {
+        order_id: OrderId,
+        quantity: Quantity,
+        reason: CancelReason,
+    },
+
+    // Operation on the order was refused, the book is left untouched
+    Rejected {
+        order_id: OrderId,
+        reason: OrderError,
+    },
+
+    // Order price or quantity was replaced, quantity is the leaves quantity
+    Replaced {
+        order_id: OrderId,
+        price: Price,
+        quantity: Quantity,
+    },
+
+    // Order quantity reduced by the book, quantity is the leaves quantity
+    Reduced {
+        order_id: OrderId,
+        quantity: Quantity,
+    },
+
+    // Order did not trade with the resting order of the same owner
+    SelfTradePrevented {
+        order_id: OrderId,
+        resting_order_id: OrderId,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidityRole {
+    Maker, // Order resting at the book
+    Taker, // Incoming order
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelReason {
+    Requested,           // Cancelled by the owner
+    ImmediateOrCancel,   // Remaining quantity of the immediate-or-cancel order
+    FillOrKill,          // Fill-or-kill order could not be filled completely
+    OneCancelsOther,     // Linked order was filled
+    SelfTradePrevention, // Order would trade with an order of the same owner
+    NoLiquidity,         // Remaining quantity of the market order, nothing left to take
+    Expired,             // Expire time of the DAY, GTD or GTT order has passed
+}
@@ -0,0 +1,28 @@
+use crate::core::order::OrderId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OcoMode {
+    Cancel, // Any fill on one leg cancels the other leg
+    Reduce, // Fill on one leg reduces the other leg by the filled quantity
+}
+
+// One leg of One-Cancels-Other pair
+#[derive(Debug, Clone, Copy)]
+pub struct OrderLink {
+    linked_id: OrderId,
+    mode: OcoMode,
+}
+
+impl OrderLink {
+    pub fn new(linked_id: OrderId, mode: OcoMode) -> Self {
+        Self { linked_id, mode }
+    }
+
+    pub fn linked_id(&self) -> OrderId {
+        self.linked_id
+    }
+
+    pub fn mode(&self) -> OcoMode {
+        self.mode
+    }
+}
@@ -0,0 +1,144 @@
+use crate::core::{
+    order::{OrderId, OrderSide, Price, Quantity},
+    order_error::OrderError,
+    order_event::{CancelReason, LiquidityRole, OrderEvent},
+};
+
+/// Callbacks invoked by the book as the events happen, every method defaults to no-op
+pub trait OrderListener {
+    // Book skips the notification work entirely when disabled
+    const ENABLED: bool = true;
+
+    fn on_accept(&mut self, _order_id: OrderId) {}
+
+    fn on_rest(&mut self, _order_id: OrderId, _price: Price, _quantity: Quantity) {}
+
+    // Called for both the taker and the maker
+    fn on_fill(
+        &mut self,
+        _order_id: OrderId,
+        _contra_order_id: OrderId,
+        _price: Price,
+        _quantity: Quantity,
+        _leaves_quantity: Quantity,
+        _role: LiquidityRole,
+    ) {
+    }
+
+    fn on_cancel(&mut self, _order_id: OrderId, _quantity: Quantity, _reason: CancelReason) {}
+
+    fn on_reject(&mut self, _order_id: OrderId, _reason: &OrderError) {}
+
+    fn on_replace(&mut self, _order_id: OrderId, _price: Price, _quantity: Quantity) {}
+
+    fn on_reduce(&mut self, _order_id: OrderId, _quantity: Quantity) {}
+
+    fn on_self_trade_prevented(&mut self, _order_id: OrderId, _resting_order_id: OrderId) {}
+
+    // Total quantity of the price level at bids or asks changed, 0 once the level is gone
+    fn on_book_change(&mut self, _order_side: OrderSide, _price: Price, _quantity: Quantity) {}
+}
+
+// No listener, compiled away
+impl OrderListener for () {
+    const ENABLED: bool = false;
+}
+
+// Events of the last operation, forwarded to the listener as they are emitted
+pub struct EventSink<L> {
+    events: Vec<OrderEvent>,
+    listener: L,
+}
+
+impl<L: OrderListener> EventSink<L> {
+    #[inline(always)]
+    pub fn new(listener: L) -> Self {
+        Self {
+            events: Vec::new(),
+            listener,
+        }
+    }
+
+    #[inline(always)]
+    pub fn events(&self) -> &[OrderEvent] {
+        &self.events
+    }
+
+    #[inline(always)]
+    pub fn listener(&self) -> &L {
+        &self.listener
+    }
+
+    #[inline(always)]
+    pub fn listener_mut(&mut self) -> &mut L {
+        &mut self.listener
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    #[inline(always)]
+    pub fn emit(&mut self, event: OrderEvent) {
+        if L::ENABLED {
+            self.notify(&event);
+        }
+
+        self.events.push(event);
+    }
+
+    #[inline(always)]
+    pub fn book_changed(&mut self, order_side: OrderSide, price: Price, quantity: Quantity) {
+        if L::ENABLED {
+            self.listener.on_book_change(order_side, price, quantity);
+        }
+    }
+
+    #[inline(always)]
+    fn notify(&mut self, event: &OrderEvent) {
+        let listener = &mut self.listener;
+        match *event {
+            OrderEvent::Accepted { order_id } => listener.on_accept(order_id),
+            OrderEvent::Rested {
+                order_id,
+                price,
+                quantity,
+            } => listener.on_rest(order_id, price, quantity),
+            OrderEvent::Fill {
+                order_id,
+                contra_order_id,
+                price,
+                quantity,
+                leaves_quantity,
+                role,
+            } => listener.on_fill(
+                order_id,
+                contra_order_id,
+                price,
+                quantity,
+                leaves_quantity,
+                role,
+            ),
+            OrderEvent::Cancelled {
+                order_id,
+                quantity,
+                reason,
+            } => listener.on_cancel(order_id, quantity, reason),
+            OrderEvent::Rejected {
+                order_id,
+                ref reason,
+            } => listener.on_reject(order_id, reason),
+            OrderEvent::Replaced {
+                order_id,
+                price,
+                quantity,
+            } => listener.on_replace(order_id, price, quantity),
+            OrderEvent::Reduced { order_id, quantity } => listener.on_reduce(order_id, quantity),
+            OrderEvent::SelfTradePrevented {
+                order_id,
+                resting_order_id,
+            } => listener.on_self_trade_prevented(order_id, resting_order_id),
+        }
+    }
+}
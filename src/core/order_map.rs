@@ -1,8 +1,12 @@
-use std::{collections::BTreeMap, fmt::Display};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    ops::Bound::{Excluded, Unbounded},
+};
 
 use crate::{
     core::order::{OrderId, OrderSide, Quantity},
-    core::orders::{Orders, SlabIndex},
+    core::orders::{OrderMeta, Orders, QueuePosition, SlabIndex},
 };
 
 pub struct OrderMap<P> {
@@ -48,13 +52,15 @@ impl<P: Ord + Clone + Display> OrderMap<P> {
         order_idx: SlabIndex,
         order_id: OrderId,
         quantity: Quantity,
-    ) {
-        self.orders
+    ) -> QueuePosition {
+        let position = self
+            .orders
             .entry(key.clone())
             .or_insert_with(Orders::new)
             .add(order_idx, order_id, quantity);
 
         self.total_quantity += quantity;
+        position
     }
 
     #[inline(always)]
@@ -72,11 +78,54 @@ impl<P: Ord + Clone + Display> OrderMap<P> {
         self.orders.remove(key)
     }
 
+    // Remove a single order from the price level, dropping the level once empty
+    #[inline(always)]
+    pub fn remove_order(
+        &mut self,
+        key: &P,
+        position: QueuePosition,
+        quantity: Quantity,
+    ) -> Option<OrderMeta> {
+        let orders = self.orders.get_mut(key)?;
+        let order_meta = orders.remove(position)?;
+
+        orders.set_orders_quantity(orders.orders_quantity() - quantity);
+        if orders.len() == 0 {
+            self.orders.remove(key);
+        }
+
+        self.total_quantity -= quantity;
+        Some(order_meta)
+    }
+
+    // Decrease the quantity of the price level, the order keeps its position
+    #[inline(always)]
+    pub fn reduce_quantity(&mut self, key: &P, quantity: Quantity) {
+        if let Some(orders) = self.orders.get_mut(key) {
+            orders.set_orders_quantity(orders.orders_quantity() - quantity);
+            self.total_quantity -= quantity;
+        }
+    }
+
     #[inline(always)]
     pub fn peek_key(&self) -> Option<&P> {
         self.orders.keys().next()
     }
 
+    // Key following the given key, which may not exist anymore
+    #[inline(always)]
+    pub fn next_key(&self, key: &P) -> Option<&P> {
+        self.orders
+            .range((Excluded(key), Unbounded))
+            .next()
+            .map(|(key, _)| key)
+    }
+
+    #[inline(always)]
+    pub fn peek_back_key(&self) -> Option<&P> {
+        self.orders.keys().next_back()
+    }
+
     #[inline(always)]
     pub fn peek_mut(&mut self) -> Option<(&P, &mut Orders)> {
         self.orders.iter_mut().next()
@@ -1,6 +1,7 @@
-use crate::core::order::{OrderId, OrderSide, Price, Quantity};
+use crate::core::order::{OrderId, OrderSide, Price, Quantity, Timestamp};
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OrderMatch {
     pub order_side: OrderSide,
     pub price: Price,
@@ -8,4 +9,7 @@ pub struct OrderMatch {
 
     pub match_from_id: OrderId,
     pub match_to_id: OrderId,
+
+    // Time of the operation the match happened in
+    pub timestamp: Timestamp,
 }
@@ -0,0 +1,14 @@
+use crate::core::{order::Quantity, order_match::OrderMatch};
+
+#[derive(Debug, PartialEq)]
+pub enum ReplaceOutcome {
+    // Quantity reduced in place, the order keeps its queue position
+    InPlace { quantity: Quantity },
+
+    // Reduced All-Or-None order could be filled completely and traded
+    Filled { matches: Vec<OrderMatch> },
+
+    // Price change or size increase, the order moves to the back of the queue
+    // and may match at its new price
+    Requeued { matches: Vec<OrderMatch> },
+}
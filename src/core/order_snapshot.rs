@@ -0,0 +1,30 @@
+use thiserror::Error;
+
+use crate::{codec::CodecError, core::order_error::OrderError};
+
+// Leading bytes of every book snapshot
+pub const SNAPSHOT_MAGIC: [u8; 4] = *b"MFSB";
+
+// Bumped whenever the layout changes, restore rejects any other version
+pub const SNAPSHOT_VERSION: u16 = 2;
+
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum SnapshotError {
+    #[error("Not a book snapshot")]
+    InvalidMagic,
+
+    #[error("Unsupported snapshot version {0}")]
+    UnsupportedVersion(u16),
+
+    #[error("Snapshot checksum mismatch")]
+    ChecksumMismatch,
+
+    #[error("Snapshot codec error: {0}")]
+    Codec(#[from] CodecError),
+
+    #[error(transparent)]
+    Order(#[from] OrderError),
+
+    #[error("Restored book is inconsistent: {0}")]
+    InconsistentBook(String),
+}
@@ -1,15 +1,26 @@
 use crate::core::order::{
-    ExecutionCondition, Order, OrderId, OrderSide, OrderType, Price, Quantity, TimeInForce,
+    ExecutionCondition, MarketProtection, Order, OrderId, OrderSide, OrderType, OwnerId, PostOnly,
+    Price, Quantity, SelfTradePrevention, TimeInForce, Timestamp, TrailingOffset,
 };
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OrderSpec {
     // Unique identifier for the order
     pub id: OrderId,
 
+    // Account owning the order
+    pub owner_id: OwnerId,
+
     // Price of the order
     pub price: Price,
 
+    // Stop price of the order (only used by stop orders)
+    pub stop_price: Price,
+
+    // Trailing distance of the order (only used by trailing stop orders)
+    pub trailing_offset: Option<TrailingOffset>,
+
     // Quantity of the order
     pub quantity: Quantity,
 
@@ -24,6 +35,21 @@ pub struct OrderSpec {
 
     // Execution condition for the order
     pub execution_condition: ExecutionCondition,
+
+    // Post-only behaviour when crossing the spread
+    pub post_only: PostOnly,
+
+    // Self-trade prevention with orders of the same owner
+    pub self_trade_prevention: SelfTradePrevention,
+
+    // Worst price a market order may trade at (only used by market orders)
+    pub market_protection: MarketProtection,
+
+    // Expire time (only used by DAY, GTD and GTT orders)
+    pub expire_time: Timestamp,
+
+    // Time the order was accepted by the book
+    pub accept_time: Timestamp,
 }
 
 impl OrderSpec {
@@ -36,12 +62,20 @@ impl OrderSpec {
     ) -> Self {
         Self {
             id,
+            owner_id: 0,
             order_side,
             price,
+            stop_price: 0,
+            trailing_offset: None,
             quantity,
             order_type: OrderType::Limit,
             time_in_force: TimeInForce::GTC, // Default to GTC
             execution_condition: ExecutionCondition::None, // Default to None
+            post_only: PostOnly::None,
+            self_trade_prevention: SelfTradePrevention::None,
+            market_protection: MarketProtection::None,
+            expire_time: 0,
+            accept_time: 0,
         }
     }
 
@@ -51,10 +85,18 @@ impl OrderSpec {
             id,
             order_side,
             price,
-            quantity: 0,                                   // NOT BEING USED
-            order_type: OrderType::Limit,                  // NOT BEING USED
-            time_in_force: TimeInForce::GTC,               // NOT BEING USED
-            execution_condition: ExecutionCondition::None, // NOT BEING USED
+            owner_id: 0,                                      // NOT BEING USED
+            stop_price: 0,                                    // NOT BEING USED
+            trailing_offset: None,                            // NOT BEING USED
+            quantity: 0,                                      // NOT BEING USED
+            order_type: OrderType::Limit,                     // NOT BEING USED
+            time_in_force: TimeInForce::GTC,                  // NOT BEING USED
+            execution_condition: ExecutionCondition::None,    // NOT BEING USED
+            post_only: PostOnly::None,                        // NOT BEING USED
+            self_trade_prevention: SelfTradePrevention::None, // NOT BEING USED
+            market_protection: MarketProtection::None,        // NOT BEING USED
+            expire_time: 0,                                   // NOT BEING USED
+            accept_time: 0,                                   // NOT BEING USED
         }
     }
 
@@ -64,10 +106,18 @@ impl OrderSpec {
             id,
             order_side,
             price,
-            quantity: 0,                                   // NOT BEING USED
-            order_type: OrderType::Limit,                  // NOT BEING USED
-            time_in_force: TimeInForce::GTC,               // NOT BEING USED
-            execution_condition: ExecutionCondition::None, // NOT BEING USED
+            owner_id: 0,                                      // NOT BEING USED
+            stop_price: 0,                                    // NOT BEING USED
+            trailing_offset: None,                            // NOT BEING USED
+            quantity: 0,                                      // NOT BEING USED
+            order_type: OrderType::Limit,                     // NOT BEING USED
+            time_in_force: TimeInForce::GTC,                  // NOT BEING USED
+            execution_condition: ExecutionCondition::None,    // NOT BEING USED
+            post_only: PostOnly::None,                        // NOT BEING USED
+            self_trade_prevention: SelfTradePrevention::None, // NOT BEING USED
+            market_protection: MarketProtection::None,        // NOT BEING USED
+            expire_time: 0,                                   // NOT BEING USED
+            accept_time: 0,                                   // NOT BEING USED
         }
     }
 
@@ -75,12 +125,99 @@ impl OrderSpec {
     pub fn market(id: OrderId, order_side: OrderSide, quantity: Quantity) -> Self {
         Self {
             id: id,
+            owner_id: 0,
             order_side,
             quantity,
             price: 0,
+            stop_price: 0,
+            trailing_offset: None,
             order_type: OrderType::Market,
             time_in_force: TimeInForce::GTC, // Default to GTC
             execution_condition: ExecutionCondition::None, // Default to None
+            post_only: PostOnly::None,
+            self_trade_prevention: SelfTradePrevention::None,
+            market_protection: MarketProtection::None,
+            expire_time: 0,
+            accept_time: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn stop_market(
+        id: OrderId,
+        order_side: OrderSide,
+        stop_price: Price,
+        quantity: Quantity,
+    ) -> Self {
+        Self {
+            id,
+            owner_id: 0,
+            order_side,
+            quantity,
+            price: 0,
+            stop_price,
+            trailing_offset: None,
+            order_type: OrderType::StopMarket,
+            time_in_force: TimeInForce::GTC, // Default to GTC
+            execution_condition: ExecutionCondition::None, // Default to None
+            post_only: PostOnly::None,
+            self_trade_prevention: SelfTradePrevention::None,
+            market_protection: MarketProtection::None,
+            expire_time: 0,
+            accept_time: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn stop_limit(
+        id: OrderId,
+        order_side: OrderSide,
+        stop_price: Price,
+        price: Price,
+        quantity: Quantity,
+    ) -> Self {
+        Self {
+            id,
+            owner_id: 0,
+            order_side,
+            quantity,
+            price,
+            stop_price,
+            trailing_offset: None,
+            order_type: OrderType::StopLimit,
+            time_in_force: TimeInForce::GTC, // Default to GTC
+            execution_condition: ExecutionCondition::None, // Default to None
+            post_only: PostOnly::None,
+            self_trade_prevention: SelfTradePrevention::None,
+            market_protection: MarketProtection::None,
+            expire_time: 0,
+            accept_time: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn trailing_stop(
+        id: OrderId,
+        order_side: OrderSide,
+        trailing_offset: TrailingOffset,
+        quantity: Quantity,
+    ) -> Self {
+        Self {
+            id,
+            owner_id: 0,
+            order_side,
+            quantity,
+            price: 0,
+            stop_price: 0, // Follow the market price
+            trailing_offset: Some(trailing_offset),
+            order_type: OrderType::TrailingStop,
+            time_in_force: TimeInForce::GTC, // Default to GTC
+            execution_condition: ExecutionCondition::None, // Default to None
+            post_only: PostOnly::None,
+            self_trade_prevention: SelfTradePrevention::None,
+            market_protection: MarketProtection::None,
+            expire_time: 0,
+            accept_time: 0,
         }
     }
 }
@@ -91,6 +228,16 @@ impl Order for OrderSpec {
         self.id
     }
 
+    #[inline(always)]
+    fn owner_id(&self) -> OwnerId {
+        self.owner_id
+    }
+
+    #[inline(always)]
+    fn set_owner_id(&mut self, owner_id: OwnerId) {
+        self.owner_id = owner_id;
+    }
+
     #[inline(always)]
     fn price(&self) -> Price {
         self.price
@@ -121,6 +268,26 @@ impl Order for OrderSpec {
         return self.order_type;
     }
 
+    #[inline(always)]
+    fn set_order_type(&mut self, order_type: OrderType) {
+        self.order_type = order_type;
+    }
+
+    #[inline(always)]
+    fn stop_price(&self) -> Price {
+        self.stop_price
+    }
+
+    #[inline(always)]
+    fn set_stop_price(&mut self, stop_price: Price) {
+        self.stop_price = stop_price;
+    }
+
+    #[inline(always)]
+    fn trailing_offset(&self) -> Option<TrailingOffset> {
+        self.trailing_offset
+    }
+
     #[inline(always)]
     fn is_buy(&self) -> bool {
         self.order_side.is_buy()
@@ -145,4 +312,59 @@ impl Order for OrderSpec {
     fn set_time_in_force(&mut self, time_in_force: TimeInForce) {
         self.time_in_force = time_in_force;
     }
+
+    #[inline(always)]
+    fn set_execution_condition(&mut self, execution_condition: ExecutionCondition) {
+        self.execution_condition = execution_condition;
+    }
+
+    #[inline(always)]
+    fn post_only(&self) -> PostOnly {
+        self.post_only
+    }
+
+    #[inline(always)]
+    fn set_post_only(&mut self, post_only: PostOnly) {
+        self.post_only = post_only;
+    }
+
+    #[inline(always)]
+    fn self_trade_prevention(&self) -> SelfTradePrevention {
+        self.self_trade_prevention
+    }
+
+    #[inline(always)]
+    fn set_self_trade_prevention(&mut self, self_trade_prevention: SelfTradePrevention) {
+        self.self_trade_prevention = self_trade_prevention;
+    }
+
+    #[inline(always)]
+    fn market_protection(&self) -> MarketProtection {
+        self.market_protection
+    }
+
+    #[inline(always)]
+    fn set_market_protection(&mut self, market_protection: MarketProtection) {
+        self.market_protection = market_protection;
+    }
+
+    #[inline(always)]
+    fn expire_time(&self) -> Timestamp {
+        self.expire_time
+    }
+
+    #[inline(always)]
+    fn set_expire_time(&mut self, expire_time: Timestamp) {
+        self.expire_time = expire_time;
+    }
+
+    #[inline(always)]
+    fn accept_time(&self) -> Timestamp {
+        self.accept_time
+    }
+
+    #[inline(always)]
+    fn set_accept_time(&mut self, accept_time: Timestamp) {
+        self.accept_time = accept_time;
+    }
 }
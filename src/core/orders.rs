@@ -4,14 +4,30 @@ use crate::core::order::{OrderId, Quantity};
 
 pub type SlabIndex = u32;
 
+// Position of the order at its price level, only increasing so it stays valid
+// while the orders in front of it are removed
+pub type QueuePosition = u64;
+
 #[derive(Debug, Clone, Copy)]
 pub struct OrderMeta {
     slab_idx: SlabIndex,
     order_id: OrderId,
 }
 
+// Where the order rests at the book, used to find it from the order id
+#[derive(Debug, Clone, Copy)]
+pub struct OrderLocation {
+    slab_idx: SlabIndex,
+    position: QueuePosition,
+}
+
 pub struct Orders {
-    items: VecDeque<OrderMeta>,
+    // Orders by position, removed orders leave an empty slot behind which is trimmed
+    // once it reaches either end, or dropped once empty slots are more than half
+    items: VecDeque<(QueuePosition, Option<OrderMeta>)>,
+    next_position: QueuePosition,
+    empty_len: u32,
+    orders_len: u32,
     orders_quantity: Quantity,
 }
 
@@ -29,43 +45,128 @@ impl OrderMeta {
     }
 }
 
+impl OrderLocation {
+    pub fn new(slab_idx: SlabIndex, position: QueuePosition) -> Self {
+        Self { slab_idx, position }
+    }
+
+    pub fn slab_idx(&self) -> SlabIndex {
+        self.slab_idx
+    }
+
+    pub fn position(&self) -> QueuePosition {
+        self.position
+    }
+}
+
 impl Orders {
     #[inline(always)]
     pub fn new() -> Self {
         return Orders {
             items: VecDeque::new(),
+            next_position: 0,
+            empty_len: 0,
+            orders_len: 0,
             orders_quantity: 0,
         };
     }
 
     #[inline(always)]
-    pub fn add(&mut self, order_idx: SlabIndex, order_id: OrderId, quantity: Quantity) {
-        self.items.push_back(OrderMeta::new(order_idx, order_id));
+    pub fn add(
+        &mut self,
+        order_idx: SlabIndex,
+        order_id: OrderId,
+        quantity: Quantity,
+    ) -> QueuePosition {
+        let position = self.next_position;
+        self.next_position += 1;
+        self.items
+            .push_back((position, Some(OrderMeta::new(order_idx, order_id))));
+        self.orders_len += 1;
         self.orders_quantity += quantity;
+
+        position
     }
 
     #[inline(always)]
     pub fn len(&self) -> u32 {
-        return self.items.len() as u32;
+        return self.orders_len;
     }
 
     #[inline(always)]
     pub fn pop_front(&mut self) -> Option<OrderMeta> {
-        if let Some(front) = self.items.pop_front() {
-            return Some(front);
+        let position = self.iter_positions().next()?.0;
+        self.remove(position)
+    }
+
+    // Remove the order at the given position, in constant time until the
+    // empty slots are dropped
+    #[inline(always)]
+    pub fn remove(&mut self, position: QueuePosition) -> Option<OrderMeta> {
+        let index = self.index(position);
+        let (item_position, item) = self.items.get_mut(index)?;
+        if *item_position != position {
+            return None;
+        }
+
+        let order_meta = item.take()?;
+        self.orders_len -= 1;
+        self.empty_len += 1;
+
+        // Trim the empty slots at both ends
+        while let Some((_, None)) = self.items.front() {
+            self.items.pop_front();
+            self.empty_len -= 1;
         }
 
-        return None;
+        while let Some((_, None)) = self.items.back() {
+            self.items.pop_back();
+            self.empty_len -= 1;
+        }
+
+        // Drop the empty slots left in the middle once they're the most of the queue
+        if self.empty_len > self.orders_len {
+            self.items.retain(|(_, item)| item.is_some());
+            self.empty_len = 0;
+        }
+
+        Some(order_meta)
+    }
+
+    #[inline(always)]
+    pub fn get(&self, position: QueuePosition) -> Option<&OrderMeta> {
+        match self.items.get(self.index(position))? {
+            (item_position, Some(order_meta)) if *item_position == position => Some(order_meta),
+            _ => None,
+        }
+    }
+
+    // First order at or after the given position, along with its position
+    #[inline(always)]
+    pub fn get_from(&self, position: QueuePosition) -> Option<(QueuePosition, &OrderMeta)> {
+        self.items
+            .range(self.index(position)..)
+            .find_map(|(position, item)| item.as_ref().map(|item| (*position, item)))
     }
 
+    // Orders at the price level in time priority
     #[inline(always)]
-    pub fn items(&self) -> &VecDeque<OrderMeta> {
-        &self.items
+    pub fn iter(&self) -> impl Iterator<Item = &OrderMeta> {
+        self.items.iter().filter_map(|(_, item)| item.as_ref())
     }
 
+    // Orders at the price level in time priority, along with their position
     #[inline(always)]
-    pub fn items_mut(&mut self) -> &mut VecDeque<OrderMeta> {
-        &mut self.items
+    pub fn iter_positions(&self) -> impl Iterator<Item = (QueuePosition, &OrderMeta)> {
+        self.items
+            .iter()
+            .filter_map(|(position, item)| item.as_ref().map(|item| (*position, item)))
+    }
+
+    // Empty slots still kept between the orders
+    #[inline(always)]
+    pub fn empty_len(&self) -> u32 {
+        self.empty_len
     }
 
     #[inline(always)]
@@ -77,4 +178,21 @@ impl Orders {
     pub fn set_orders_quantity(&mut self, quantity: Quantity) {
         self.orders_quantity = quantity;
     }
+
+    // Index of the first slot at or after the position, slots are one position
+    // apart until the empty slots are dropped
+    #[inline(always)]
+    fn index(&self, position: QueuePosition) -> usize {
+        let Some(&(front_position, _)) = self.items.front() else {
+            return 0;
+        };
+
+        let index = position.saturating_sub(front_position) as usize;
+        match self.items.get(index) {
+            Some(&(item_position, _)) if item_position == position => index,
+            _ => self
+                .items
+                .partition_point(|&(item_position, _)| item_position < position),
+        }
+    }
 }
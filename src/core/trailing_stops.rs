@@ -0,0 +1,105 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    core::order::{OrderId, OrderSide, Price},
+    utils::IdHashMap,
+};
+
+/// Trailing stop orders by the market price their stop price was last trailed from.
+/// The stop price of a sell order only moves once the market goes above that price and
+/// the stop price of a buy order only once it goes below, so a market move only touches
+/// the orders past the new market price instead of every trailing stop order.
+#[derive(Debug, Clone, Default)]
+pub struct TrailingStops {
+    anchors: IdHashMap<OrderId, (OrderSide, Price)>,
+    sells: BTreeSet<(Price, OrderId)>,
+    buys: BTreeSet<(Price, OrderId)>,
+}
+
+impl TrailingStops {
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.anchors.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.anchors.is_empty()
+    }
+
+    // Stop price trailed from `market_price`, 0 for a sell and Price::MAX for a buy
+    // order that moves on any market price
+    #[inline(always)]
+    pub fn insert(&mut self, order_id: OrderId, order_side: OrderSide, market_price: Price) {
+        self.remove(order_id);
+        self.anchors.insert(order_id, (order_side, market_price));
+        if order_side.is_buy() {
+            self.buys.insert((market_price, order_id));
+        } else {
+            self.sells.insert((market_price, order_id));
+        }
+    }
+
+    #[inline(always)]
+    pub fn remove(&mut self, order_id: OrderId) {
+        let Some((order_side, market_price)) = self.anchors.remove(&order_id) else {
+            return;
+        };
+
+        if order_side.is_buy() {
+            self.buys.remove(&(market_price, order_id));
+        } else {
+            self.sells.remove(&(market_price, order_id));
+        }
+    }
+
+    /// Orders whose stop price may move at the new market price, by order id.
+    /// They're trailed from the new market price from here on.
+    pub fn pop_moving(&mut self, market_price: Price) -> Vec<OrderId> {
+        let mut order_ids = Vec::new();
+
+        while let Some(&(anchor, order_id)) = self.sells.first() {
+            if anchor >= market_price {
+                break;
+            }
+
+            self.sells.pop_first();
+            order_ids.push(order_id);
+        }
+
+        while let Some(&(anchor, order_id)) = self.buys.last() {
+            if anchor <= market_price {
+                break;
+            }
+
+            self.buys.pop_last();
+            order_ids.push(order_id);
+        }
+
+        for &order_id in order_ids.iter() {
+            let (order_side, anchor) = self.anchors.get_mut(&order_id).unwrap();
+            *anchor = market_price;
+            if order_side.is_buy() {
+                self.buys.insert((market_price, order_id));
+            } else {
+                self.sells.insert((market_price, order_id));
+            }
+        }
+
+        // Same queue order at the new stop price level whatever the anchors were
+        order_ids.sort_unstable();
+        order_ids
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.anchors.clear();
+        self.sells.clear();
+        self.buys.clear();
+    }
+}
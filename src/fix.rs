@@ -0,0 +1,681 @@
+use std::fmt::Display;
+
+use thiserror::Error;
+
+use crate::{
+    clock::Clock,
+    core::{
+        order::{
+            ExecutionCondition, Order, OrderId, OrderSide, OwnerId, Price, Quantity, TimeInForce,
+            Timestamp,
+        },
+        order_book::OrderBook,
+        order_event::{CancelReason, OrderEvent},
+        order_listener::OrderListener,
+        order_spec::OrderSpec,
+    },
+    utils::IdHashMap,
+};
+
+pub const BEGIN_STRING: &str = "FIX.4.4";
+const SOH: u8 = 0x01;
+
+// Tags used by the order entry messages
+pub mod tag {
+    pub const ACCOUNT: u32 = 1;
+    pub const AVG_PX: u32 = 6;
+    pub const BEGIN_STRING: u32 = 8;
+    pub const BODY_LENGTH: u32 = 9;
+    pub const CHECK_SUM: u32 = 10;
+    pub const CL_ORD_ID: u32 = 11;
+    pub const CUM_QTY: u32 = 14;
+    pub const EXEC_ID: u32 = 17;
+    pub const EXEC_INST: u32 = 18;
+    pub const LAST_PX: u32 = 31;
+    pub const LAST_QTY: u32 = 32;
+    pub const MSG_SEQ_NUM: u32 = 34;
+    pub const MSG_TYPE: u32 = 35;
+    pub const ORDER_ID: u32 = 37;
+    pub const ORDER_QTY: u32 = 38;
+    pub const ORD_STATUS: u32 = 39;
+    pub const ORD_TYPE: u32 = 40;
+    pub const ORIG_CL_ORD_ID: u32 = 41;
+    pub const PRICE: u32 = 44;
+    pub const SENDER_COMP_ID: u32 = 49;
+    pub const SENDING_TIME: u32 = 52;
+    pub const SIDE: u32 = 54;
+    pub const TARGET_COMP_ID: u32 = 56;
+    pub const TEXT: u32 = 58;
+    pub const TIME_IN_FORCE: u32 = 59;
+    pub const TRANSACT_TIME: u32 = 60;
+    pub const STOP_PX: u32 = 99;
+    pub const CXL_REJ_REASON: u32 = 102;
+    pub const EXPIRE_TIME: u32 = 126;
+    pub const EXEC_TYPE: u32 = 150;
+    pub const LEAVES_QTY: u32 = 151;
+    pub const EXPIRE_DATE: u32 = 432;
+    pub const CXL_REJ_RESPONSE_TO: u32 = 434;
+}
+
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum FixError {
+    #[error("Malformed message")]
+    Malformed,
+
+    #[error("Unsupported begin string")]
+    InvalidBeginString,
+
+    #[error("Body length mismatch")]
+    InvalidBodyLength,
+
+    #[error("Checksum mismatch")]
+    InvalidCheckSum,
+
+    #[error("Unsupported message type {0}")]
+    UnsupportedMsgType(String),
+
+    #[error("Missing tag {0}")]
+    MissingTag(u32),
+
+    #[error("Invalid value {1} of tag {0}")]
+    InvalidValue(u32, String),
+}
+
+/// Tag value pairs of a message between the body length and the checksum, in order
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixMessage {
+    fields: Vec<(u32, String)>,
+}
+
+impl FixMessage {
+    pub fn new(msg_type: &str) -> Self {
+        Self {
+            fields: vec![(tag::MSG_TYPE, msg_type.to_string())],
+        }
+    }
+
+    /// Parse a full message, checking the begin string, body length and checksum
+    pub fn parse(bytes: &[u8]) -> Result<Self, FixError> {
+        let text = std::str::from_utf8(bytes).map_err(|_| FixError::Malformed)?;
+        let text = text.strip_suffix(SOH as char).ok_or(FixError::Malformed)?;
+
+        let mut fields = Vec::new();
+        for field in text.split(SOH as char) {
+            let (tag, value) = field.split_once('=').ok_or(FixError::Malformed)?;
+            let tag = tag.parse().map_err(|_| FixError::Malformed)?;
+            if value.is_empty() {
+                return Err(FixError::Malformed);
+            }
+            fields.push((tag, value.to_string()));
+        }
+
+        if fields.len() < 4 || fields[0] != (tag::BEGIN_STRING, BEGIN_STRING.to_string()) {
+            return Err(FixError::InvalidBeginString);
+        }
+
+        // Body runs from the field after the body length up to the checksum
+        let (check_sum_tag, check_sum) = fields.pop().unwrap();
+        let check_sum_start = bytes.len() - check_sum.len() - 4;
+        if check_sum_tag != tag::CHECK_SUM
+            || check_sum.len() != 3
+            || check_sum != format!("{:03}", Self::check_sum(&bytes[..check_sum_start]))
+        {
+            return Err(FixError::InvalidCheckSum);
+        }
+
+        let (body_length_tag, body_length) = &fields[1];
+        let body_start = fields[0].1.len() + body_length.len() + 6;
+        if *body_length_tag != tag::BODY_LENGTH
+            || body_length.parse() != Ok(check_sum_start - body_start)
+        {
+            return Err(FixError::InvalidBodyLength);
+        }
+
+        Ok(Self {
+            fields: fields.split_off(2),
+        })
+    }
+
+    #[inline(always)]
+    pub fn msg_type(&self) -> &str {
+        self.get(tag::MSG_TYPE).unwrap_or_default()
+    }
+
+    #[inline(always)]
+    pub fn fields(&self) -> &[(u32, String)] {
+        &self.fields
+    }
+
+    // First value of the tag
+    #[inline(always)]
+    pub fn get(&self, tag: u32) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field_tag, _)| *field_tag == tag)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[inline(always)]
+    pub fn push(&mut self, tag: u32, value: impl Display) {
+        self.fields.push((tag, value.to_string()));
+    }
+
+    #[inline(always)]
+    pub fn with(mut self, tag: u32, value: impl Display) -> Self {
+        self.push(tag, value);
+        self
+    }
+
+    /// Full message with the begin string, body length and checksum around the fields
+    pub fn encode(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (tag, value) in &self.fields {
+            body.extend_from_slice(format!("{}={}", tag, value).as_bytes());
+            body.push(SOH);
+        }
+
+        let mut bytes = format!("8={}\x019={}\x01", BEGIN_STRING, body.len()).into_bytes();
+        bytes.extend_from_slice(&body);
+
+        let check_sum = Self::check_sum(&bytes);
+        bytes.extend_from_slice(format!("10={:03}\x01", check_sum).as_bytes());
+        bytes
+    }
+
+    fn required(&self, tag: u32) -> Result<&str, FixError> {
+        self.get(tag).ok_or(FixError::MissingTag(tag))
+    }
+
+    fn required_number<V: std::str::FromStr>(&self, tag: u32) -> Result<V, FixError> {
+        let value = self.required(tag)?;
+        value
+            .parse()
+            .map_err(|_| FixError::InvalidValue(tag, value.to_string()))
+    }
+
+    #[inline(always)]
+    fn check_sum(bytes: &[u8]) -> u8 {
+        bytes
+            .iter()
+            .fold(0u8, |check_sum, &byte| check_sum.wrapping_add(byte))
+    }
+}
+
+/// Book command carried by an order entry message.
+/// ClOrdID (11) of a new order is the book order id and must be numeric, cancel and replace
+/// find the order by OrigClOrdID (41) and the order keeps that id. Their own ClOrdID is
+/// only reported back. Prices are book prices in whole units, decimals like 101.5 are rejected.
+#[derive(Debug, Clone)]
+pub enum FixCommand {
+    // NewOrderSingle (D)
+    NewOrder(OrderSpec),
+
+    // OrderCancelRequest (F)
+    Cancel {
+        order_id: OrderId,
+        cl_ord_id: String,
+    },
+
+    // OrderCancelReplaceRequest (G), OrderQty (38) is the new total quantity including
+    // what already filled, price is 0 when Price (44) is left out to keep the current price
+    Replace {
+        order_id: OrderId,
+        cl_ord_id: String,
+        quantity: Quantity,
+        price: Price,
+    },
+}
+
+impl FixCommand {
+    pub fn parse(message: &FixMessage) -> Result<Self, FixError> {
+        match message.msg_type() {
+            "D" => Ok(FixCommand::NewOrder(Self::parse_order(message)?)),
+            "F" => Ok(FixCommand::Cancel {
+                order_id: message.required_number(tag::ORIG_CL_ORD_ID)?,
+                cl_ord_id: message.required(tag::CL_ORD_ID)?.to_string(),
+            }),
+            "G" => Ok(FixCommand::Replace {
+                order_id: message.required_number(tag::ORIG_CL_ORD_ID)?,
+                cl_ord_id: message.required(tag::CL_ORD_ID)?.to_string(),
+                quantity: message.required_number(tag::ORDER_QTY)?,
+                price: match message.get(tag::PRICE) {
+                    Some(_) => message.required_number(tag::PRICE)?,
+                    None => 0,
+                },
+            }),
+            msg_type => Err(FixError::UnsupportedMsgType(msg_type.to_string())),
+        }
+    }
+
+    fn parse_order(message: &FixMessage) -> Result<OrderSpec, FixError> {
+        let id: OrderId = message.required_number(tag::CL_ORD_ID)?;
+        let quantity: Quantity = message.required_number(tag::ORDER_QTY)?;
+        let order_side = match message.required(tag::SIDE)? {
+            "1" => OrderSide::Buy,
+            "2" => OrderSide::Sell,
+            side => return Err(FixError::InvalidValue(tag::SIDE, side.to_string())),
+        };
+
+        let mut order = match message.required(tag::ORD_TYPE)? {
+            "1" => OrderSpec::market(id, order_side, quantity),
+            "2" => OrderSpec::limit_price(
+                id,
+                order_side,
+                message.required_number(tag::PRICE)?,
+                quantity,
+            ),
+            "3" => OrderSpec::stop_market(
+                id,
+                order_side,
+                message.required_number(tag::STOP_PX)?,
+                quantity,
+            ),
+            "4" => OrderSpec::stop_limit(
+                id,
+                order_side,
+                message.required_number(tag::STOP_PX)?,
+                message.required_number(tag::PRICE)?,
+                quantity,
+            ),
+            ord_type => return Err(FixError::InvalidValue(tag::ORD_TYPE, ord_type.to_string())),
+        };
+
+        // GTD expires at the end of ExpireDate, or at ExpireTime which makes it good till time
+        let time_in_force = match message.get(tag::TIME_IN_FORCE).unwrap_or("0") {
+            "0" => TimeInForce::DAY,
+            "1" => TimeInForce::GTC,
+            "3" => TimeInForce::IOC,
+            "4" => TimeInForce::FOK,
+            "6" if message.get(tag::EXPIRE_TIME).is_some() => {
+                let expire_time = message.required(tag::EXPIRE_TIME)?;
+                order.set_expire_time(parse_utc_timestamp(expire_time).ok_or(
+                    FixError::InvalidValue(tag::EXPIRE_TIME, expire_time.to_string()),
+                )?);
+                TimeInForce::GTT
+            }
+            "6" => {
+                let expire_date = message.required(tag::EXPIRE_DATE)?;
+                let expire_time = parse_utc_timestamp(&format!("{}-00:00:00", expire_date)).ok_or(
+                    FixError::InvalidValue(tag::EXPIRE_DATE, expire_date.to_string()),
+                )?;
+                order.set_expire_time(expire_time + NANOS_PER_DAY);
+                TimeInForce::GTD
+            }
+            time_in_force => {
+                return Err(FixError::InvalidValue(
+                    tag::TIME_IN_FORCE,
+                    time_in_force.to_string(),
+                ));
+            }
+        };
+        order.set_time_in_force(time_in_force);
+
+        // ExecInst is a space separated list, G is all-or-none
+        if message
+            .get(tag::EXEC_INST)
+            .is_some_and(|exec_inst| exec_inst.split(' ').any(|inst| inst == "G"))
+        {
+            order.set_execution_condition(ExecutionCondition::AON);
+        }
+
+        if message.get(tag::ACCOUNT).is_some() {
+            order.set_owner_id(message.required_number::<OwnerId>(tag::ACCOUNT)?);
+        }
+
+        Ok(order)
+    }
+}
+
+// What the session knows of an order it entered, to fill the execution reports
+#[derive(Debug, Clone, Copy)]
+struct FixOrder {
+    order_side: OrderSide,
+    cum_qty: Quantity,
+    notional: u128, // Sum of fill price * quantity, for the average price
+}
+
+/// Order entry session in front of a book, applies the commands and reports the
+/// outcome as ExecutionReport (8), or OrderCancelReject (9) for a refused cancel or replace
+pub struct FixSession {
+    sender_comp_id: String,
+    target_comp_id: String,
+    msg_seq_num: u64,
+    exec_id: u64,
+    orders: IdHashMap<OrderId, FixOrder>,
+}
+
+impl FixSession {
+    pub fn new(sender_comp_id: &str, target_comp_id: &str) -> Self {
+        Self {
+            sender_comp_id: sender_comp_id.to_string(),
+            target_comp_id: target_comp_id.to_string(),
+            msg_seq_num: 0,
+            exec_id: 0,
+            orders: IdHashMap::default(),
+        }
+    }
+
+    // Sequence number of the last message sent
+    #[inline(always)]
+    pub fn msg_seq_num(&self) -> u64 {
+        self.msg_seq_num
+    }
+
+    /// Parse and apply an order entry message, the messages to send back come out
+    pub fn handle<L: OrderListener, C: Clock>(
+        &mut self,
+        book: &mut OrderBook<OrderSpec, L, C>,
+        bytes: &[u8],
+    ) -> Result<Vec<FixMessage>, FixError> {
+        let command = FixCommand::parse(&FixMessage::parse(bytes)?)?;
+        Ok(self.apply(book, &command))
+    }
+
+    pub fn apply<L: OrderListener, C: Clock>(
+        &mut self,
+        book: &mut OrderBook<OrderSpec, L, C>,
+        command: &FixCommand,
+    ) -> Vec<FixMessage> {
+        let cxl_rej_response_to = match command {
+            FixCommand::NewOrder(order) => {
+                // A duplicate id is rejected by the book, the live order keeps its state
+                self.orders.entry(order.id()).or_insert(FixOrder {
+                    order_side: order.order_side(),
+                    cum_qty: 0,
+                    notional: 0,
+                });
+                _ = book.insert_order(order);
+                None
+            }
+            FixCommand::Cancel { order_id, .. } => {
+                _ = book.cancel_order_by_id(*order_id);
+                Some(1)
+            }
+            FixCommand::Replace {
+                order_id,
+                cl_ord_id,
+                quantity,
+                price,
+            } => {
+                // Leaves are what is left of the new total after the fills so far
+                let cum_qty = self.orders.get(order_id).map_or(0, |order| order.cum_qty);
+                if *quantity <= cum_qty {
+                    let mut message = self.cancel_reject(
+                        (*order_id, cl_ord_id),
+                        2,
+                        "Order quantity is not above the filled quantity",
+                    );
+                    self.stamp_header(&mut message, book.transact_time());
+                    return vec![message];
+                }
+
+                let leaves_qty = book
+                    .get_order(*order_id)
+                    .map_or(0, |order| order.quantity());
+                let quantity_delta = (*quantity - cum_qty) as i64 - leaves_qty as i64;
+                _ = book.replace_order_by_id(*order_id, quantity_delta, *price);
+                Some(2)
+            }
+        };
+
+        let (order_qty, request) = match command {
+            FixCommand::NewOrder(order) => (order.quantity(), None),
+            FixCommand::Cancel {
+                order_id,
+                cl_ord_id,
+            }
+            | FixCommand::Replace {
+                order_id,
+                cl_ord_id,
+                ..
+            } => (0, Some((*order_id, cl_ord_id.as_str()))),
+        };
+
+        let transact_time = book.transact_time();
+        let mut messages = Vec::new();
+        for event in book.events() {
+            let message = match (event, cxl_rej_response_to, request) {
+                (OrderEvent::Rejected { reason, .. }, Some(response_to), Some(request)) => {
+                    self.cancel_reject(request, response_to, reason)
+                }
+                _ => match self.execution_report(event, order_qty, request, transact_time) {
+                    Some(message) => message,
+                    None => continue,
+                },
+            };
+            messages.push(message);
+        }
+
+        if let FixCommand::NewOrder(order) = command
+            && book.get_order(order.id()).is_none()
+        {
+            self.orders.remove(&order.id());
+        }
+
+        for message in messages.iter_mut() {
+            self.stamp_header(message, transact_time);
+        }
+
+        messages
+    }
+
+    // OrderCancelReject (9) of a cancel (1) or replace (2) request, given as order id and
+    // ClOrdID of the request, unknown orders are reported rejected
+    fn cancel_reject(
+        &self,
+        (order_id, cl_ord_id): (OrderId, &str),
+        response_to: u8,
+        text: impl Display,
+    ) -> FixMessage {
+        let (ord_status, cxl_rej_reason) = match self.orders.get(&order_id) {
+            Some(order) if order.cum_qty > 0 => ('1', 99),
+            Some(_) => ('0', 99),
+            None => ('8', 1),
+        };
+
+        FixMessage::new("9")
+            .with(tag::ORDER_ID, order_id)
+            .with(tag::CL_ORD_ID, cl_ord_id)
+            .with(tag::ORIG_CL_ORD_ID, order_id)
+            .with(tag::ORD_STATUS, ord_status)
+            .with(tag::CXL_REJ_RESPONSE_TO, response_to)
+            .with(tag::CXL_REJ_REASON, cxl_rej_reason)
+            .with(tag::TEXT, text)
+    }
+
+    // Report of the event, None for the events that don't change what the client knows.
+    // Report on the order of a cancel or replace request carries the ClOrdID of the request.
+    fn execution_report(
+        &mut self,
+        event: &OrderEvent,
+        order_qty: Quantity,
+        request: Option<(OrderId, &str)>,
+        transact_time: Timestamp,
+    ) -> Option<FixMessage> {
+        let (order_id, exec_type, leaves_qty) = match *event {
+            OrderEvent::Accepted { order_id } => (order_id, '0', order_qty),
+            OrderEvent::Fill {
+                order_id,
+                leaves_quantity,
+                ..
+            } => (order_id, 'F', leaves_quantity),
+            OrderEvent::Cancelled {
+                order_id, reason, ..
+            } => {
+                let exec_type = if reason == CancelReason::Expired {
+                    'C'
+                } else {
+                    '4'
+                };
+                (order_id, exec_type, 0)
+            }
+            OrderEvent::Rejected { order_id, .. } => (order_id, '8', 0),
+            OrderEvent::Replaced {
+                order_id, quantity, ..
+            } => (order_id, '5', quantity),
+            OrderEvent::Reduced { order_id, quantity } => (order_id, 'D', quantity),
+            OrderEvent::Rested { .. } | OrderEvent::SelfTradePrevented { .. } => return None,
+        };
+
+        // Orders entered elsewhere are reported without side and fills
+        let order = self.orders.get_mut(&order_id)?;
+        if let OrderEvent::Fill {
+            price, quantity, ..
+        } = *event
+        {
+            order.cum_qty += quantity;
+            order.notional += price as u128 * quantity as u128;
+        }
+
+        let ord_status = match exec_type {
+            'F' | '5' | 'D' if leaves_qty == 0 => '2',
+            'F' | '5' | 'D' if order.cum_qty > 0 => '1',
+            'F' | '5' | 'D' => '0',
+            exec_type => exec_type,
+        };
+        let avg_px = if order.cum_qty > 0 {
+            order.notional / order.cum_qty as u128
+        } else {
+            0
+        };
+
+        self.exec_id += 1;
+        let mut message = FixMessage::new("8").with(tag::ORDER_ID, order_id);
+        match request {
+            Some((request_order_id, cl_ord_id)) if request_order_id == order_id => {
+                message.push(tag::CL_ORD_ID, cl_ord_id);
+                message.push(tag::ORIG_CL_ORD_ID, order_id);
+            }
+            _ => message.push(tag::CL_ORD_ID, order_id),
+        }
+        message = message
+            .with(tag::EXEC_ID, self.exec_id)
+            .with(tag::EXEC_TYPE, exec_type)
+            .with(tag::ORD_STATUS, ord_status)
+            .with(tag::SIDE, if order.order_side.is_buy() { 1 } else { 2 })
+            .with(tag::LEAVES_QTY, leaves_qty)
+            .with(tag::CUM_QTY, order.cum_qty)
+            .with(tag::AVG_PX, avg_px);
+
+        match event {
+            OrderEvent::Fill {
+                price, quantity, ..
+            } => {
+                message.push(tag::LAST_PX, price);
+                message.push(tag::LAST_QTY, quantity);
+            }
+            OrderEvent::Rejected { reason, .. } => message.push(tag::TEXT, reason),
+            _ => {}
+        }
+        message.push(tag::TRANSACT_TIME, format_utc_timestamp(transact_time));
+
+        // Done orders are forgotten, a rejected new order is never in the book
+        if leaves_qty == 0 && exec_type != '8' {
+            self.orders.remove(&order_id);
+        }
+
+        Some(message)
+    }
+
+    // Header fields go right after the message type
+    fn stamp_header(&mut self, message: &mut FixMessage, sending_time: Timestamp) {
+        self.msg_seq_num += 1;
+        let header = [
+            (tag::SENDER_COMP_ID, self.sender_comp_id.clone()),
+            (tag::TARGET_COMP_ID, self.target_comp_id.clone()),
+            (tag::MSG_SEQ_NUM, self.msg_seq_num.to_string()),
+            (tag::SENDING_TIME, format_utc_timestamp(sending_time)),
+        ];
+        message.fields.splice(1..1, header);
+    }
+}
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const NANOS_PER_DAY: u64 = 86_400 * NANOS_PER_SECOND;
+
+/// UTCTimestamp `YYYYMMDD-HH:MM:SS.sss` of nanoseconds since the unix epoch
+pub fn format_utc_timestamp(timestamp: Timestamp) -> String {
+    let (year, month, day) = civil_from_days((timestamp / NANOS_PER_DAY) as i64);
+    let seconds = timestamp % NANOS_PER_DAY / NANOS_PER_SECOND;
+    let millis = timestamp % NANOS_PER_SECOND / 1_000_000;
+
+    format!(
+        "{:04}{:02}{:02}-{:02}:{:02}:{:02}.{:03}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        millis
+    )
+}
+
+/// Nanoseconds since the unix epoch of a UTCTimestamp, with or without the fraction
+pub fn parse_utc_timestamp(text: &str) -> Option<Timestamp> {
+    let (date, time) = text.split_once('-')?;
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    if date.len() != 8 || time.len() != 8 || fraction.len() > 9 {
+        return None;
+    }
+
+    let number = |text: &str| text.parse::<u64>().ok();
+    let (year, month, day) = (
+        number(&date[..4])?,
+        number(&date[4..6])?,
+        number(&date[6..])?,
+    );
+    let mut clock = time.split(':');
+    let (hour, minute, second) = (
+        number(clock.next()?)?,
+        number(clock.next()?)?,
+        number(clock.next()?)?,
+    );
+    if !(1970..=9999).contains(&year)
+        || !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    let nanos = if fraction.is_empty() {
+        0
+    } else {
+        number(fraction)? * 10u64.pow(9 - fraction.len() as u32)
+    };
+    let days = days_from_civil(year as i64, month as i64, day as i64) as u64;
+
+    Some(days * NANOS_PER_DAY + (hour * 3600 + minute * 60 + second) * NANOS_PER_SECOND + nanos)
+}
+
+// Days since the unix epoch of a proleptic gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+
+    (year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
@@ -0,0 +1,653 @@
+use std::{io, marker::PhantomData};
+
+use thiserror::Error;
+
+use crate::{
+    clock::{Clock, ManualClock, SystemClock},
+    codec::{CodecError, Decoder, Encoder, OrderCodec, decode_oco_mode, encode_oco_mode},
+    core::{
+        instrument_spec::InstrumentSpec,
+        order::{Order, OrderId, Price, Timestamp},
+        order_book::OrderBook,
+        order_error::OrderError,
+        order_link::OcoMode,
+        order_listener::OrderListener,
+        order_match::OrderMatch,
+        order_replace::ReplaceOutcome,
+    },
+    utils::crc32,
+};
+
+// Length of the record, then sequence and timestamp
+const RECORD_HEADER_LEN: usize = 4 + 8 + 8;
+const RECORD_CHECKSUM_LEN: usize = 4;
+
+// Bounds of the length field, sequence and timestamp to checksum.
+// Larger lengths can only come from corruption, records stay far below it.
+const MIN_RECORD_LEN: usize = RECORD_HEADER_LEN - 4 + RECORD_CHECKSUM_LEN;
+pub const MAX_RECORD_LEN: usize = 4096;
+
+#[derive(Debug, Error)]
+pub enum JournalError {
+    #[error("Journal io error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Journal codec error: {0}")]
+    Codec(#[from] CodecError),
+
+    #[error("Checksum mismatch at sequence {sequence}")]
+    ChecksumMismatch { sequence: u64 },
+
+    #[error("Sequence gap, expected {expected} found {found}")]
+    SequenceGap { expected: u64, found: u64 },
+
+    #[error("Invalid command tag {0}")]
+    InvalidCommand(u8),
+
+    #[error("Record of sequence {sequence} is {len} bytes, above the maximum")]
+    RecordTooLarge { sequence: u64, len: usize },
+
+    #[error("Invalid record length {len} at position {position}")]
+    InvalidRecordLength { position: usize, len: usize },
+
+    #[error(transparent)]
+    Order(#[from] OrderError),
+}
+
+/// Inbound command changing the book, journaled before it's applied
+#[derive(Debug, Clone)]
+pub enum JournalCommand<T> {
+    Insert(T),
+    InsertOco {
+        order: T,
+        linked_order: T,
+        mode: OcoMode,
+    },
+    Replace {
+        order_id: OrderId,
+        quantity_delta: i64,
+        new_price: Price,
+    },
+    Cancel(OrderId),
+    TriggerStops,
+    SetMarketPrice(Price),
+    AdvanceTime(Timestamp),
+    SetDayEnd(Timestamp),
+    SetInstrumentSpec(InstrumentSpec),
+}
+
+impl<T: Order + OrderCodec> JournalCommand<T> {
+    fn encode(&self, encoder: &mut Encoder) {
+        match self {
+            JournalCommand::Insert(order) => {
+                encoder.put_u8(0);
+                order.encode(encoder);
+            }
+            JournalCommand::InsertOco {
+                order,
+                linked_order,
+                mode,
+            } => {
+                encoder.put_u8(1);
+                order.encode(encoder);
+                linked_order.encode(encoder);
+                encoder.put_u8(encode_oco_mode(*mode));
+            }
+            JournalCommand::Replace {
+                order_id,
+                quantity_delta,
+                new_price,
+            } => {
+                encoder.put_u8(2);
+                encoder.put_u32(*order_id);
+                encoder.put_i64(*quantity_delta);
+                encoder.put_u64(*new_price);
+            }
+            JournalCommand::Cancel(order_id) => {
+                encoder.put_u8(3);
+                encoder.put_u32(*order_id);
+            }
+            JournalCommand::TriggerStops => encoder.put_u8(4),
+            JournalCommand::SetMarketPrice(price) => {
+                encoder.put_u8(5);
+                encoder.put_u64(*price);
+            }
+            JournalCommand::AdvanceTime(now) => {
+                encoder.put_u8(6);
+                encoder.put_u64(*now);
+            }
+            JournalCommand::SetDayEnd(day_end) => {
+                encoder.put_u8(7);
+                encoder.put_u64(*day_end);
+            }
+            JournalCommand::SetInstrumentSpec(instrument_spec) => {
+                encoder.put_u8(8);
+                encoder.put_u64(instrument_spec.tick_size);
+                encoder.put_u64(instrument_spec.lot_size);
+                encoder.put_u64(instrument_spec.min_quantity);
+                encoder.put_u64(instrument_spec.max_quantity);
+                encoder.put_u64(instrument_spec.min_price);
+                encoder.put_u64(instrument_spec.max_price);
+                encoder.put_u128(instrument_spec.min_notional);
+            }
+        }
+    }
+
+    fn decode(decoder: &mut Decoder) -> Result<Self, JournalError> {
+        let command = match decoder.get_u8()? {
+            0 => JournalCommand::Insert(T::decode(decoder)?),
+            1 => JournalCommand::InsertOco {
+                order: T::decode(decoder)?,
+                linked_order: T::decode(decoder)?,
+                mode: decode_oco_mode(decoder.get_u8()?)?,
+            },
+            2 => JournalCommand::Replace {
+                order_id: decoder.get_u32()?,
+                quantity_delta: decoder.get_i64()?,
+                new_price: decoder.get_u64()?,
+            },
+            3 => JournalCommand::Cancel(decoder.get_u32()?),
+            4 => JournalCommand::TriggerStops,
+            5 => JournalCommand::SetMarketPrice(decoder.get_u64()?),
+            6 => JournalCommand::AdvanceTime(decoder.get_u64()?),
+            7 => JournalCommand::SetDayEnd(decoder.get_u64()?),
+            8 => JournalCommand::SetInstrumentSpec(InstrumentSpec {
+                tick_size: decoder.get_u64()?,
+                lot_size: decoder.get_u64()?,
+                min_quantity: decoder.get_u64()?,
+                max_quantity: decoder.get_u64()?,
+                min_price: decoder.get_u64()?,
+                max_price: decoder.get_u64()?,
+                min_notional: decoder.get_u128()?,
+            }),
+            tag => return Err(JournalError::InvalidCommand(tag)),
+        };
+
+        Ok(command)
+    }
+
+    // Results are dropped, replaying the same commands gives the same results
+    pub fn apply<L: OrderListener, C: Clock>(&self, book: &mut OrderBook<T, L, C>) {
+        match self {
+            JournalCommand::Insert(order) => _ = book.insert_order(order),
+            JournalCommand::InsertOco {
+                order,
+                linked_order,
+                mode,
+            } => _ = book.insert_oco_order(order, linked_order, *mode),
+            JournalCommand::Replace {
+                order_id,
+                quantity_delta,
+                new_price,
+            } => _ = book.replace_order_by_id(*order_id, *quantity_delta, *new_price),
+            JournalCommand::Cancel(order_id) => _ = book.cancel_order_by_id(*order_id),
+            JournalCommand::TriggerStops => _ = book.trigger_stop_orders(),
+            JournalCommand::SetMarketPrice(price) => book.set_market_price(*price),
+            JournalCommand::AdvanceTime(now) => _ = book.advance_time(*now),
+            JournalCommand::SetDayEnd(day_end) => book.set_day_end(*day_end),
+            JournalCommand::SetInstrumentSpec(instrument_spec) => {
+                book.set_instrument_spec(*instrument_spec)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JournalRecord<T> {
+    pub sequence: u64,
+    pub timestamp: Timestamp, // Transact time the command was applied with
+    pub command: JournalCommand<T>,
+}
+
+/// Destination of the journal records, anything implementing `io::Write` is a sink
+pub trait JournalSink {
+    fn write_record(&mut self, record: &[u8]) -> io::Result<()>;
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<W: io::Write> JournalSink for W {
+    #[inline(always)]
+    fn write_record(&mut self, record: &[u8]) -> io::Result<()> {
+        self.write_all(record)
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) -> io::Result<()> {
+        io::Write::flush(self)
+    }
+}
+
+/// Appends records to the sink, each one is
+/// `len: u32 | sequence: u64 | timestamp: u64 | command | crc32: u32`, little endian,
+/// where len counts the bytes after it and the checksum covers sequence to command
+pub struct JournalWriter<S: JournalSink> {
+    sink: S,
+    next_sequence: u64,
+    encoder: Encoder,
+}
+
+impl<S: JournalSink> JournalWriter<S> {
+    // New journal, the first record gets sequence 1
+    pub fn new(sink: S) -> Self {
+        Self::with_sequence(sink, 1)
+    }
+
+    // Continue a recovered journal
+    pub fn with_sequence(sink: S, next_sequence: u64) -> Self {
+        Self {
+            sink,
+            next_sequence,
+            encoder: Encoder::new(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    #[inline(always)]
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    #[inline(always)]
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+
+    /// Write the command, returns its sequence.
+    /// The sequence only moves forward once the sink took the record.
+    pub fn append<T: Order + OrderCodec>(
+        &mut self,
+        timestamp: Timestamp,
+        command: &JournalCommand<T>,
+    ) -> Result<u64, JournalError> {
+        let sequence = self.next_sequence;
+
+        self.encoder.clear();
+        self.encoder.put_u32(0);
+        self.encoder.put_u64(sequence);
+        self.encoder.put_u64(timestamp);
+        command.encode(&mut self.encoder);
+
+        let checksum = crc32(&self.encoder.bytes()[4..]);
+        self.encoder.put_u32(checksum);
+
+        let len = self.encoder.len() - 4;
+        if len > MAX_RECORD_LEN {
+            return Err(JournalError::RecordTooLarge { sequence, len });
+        }
+        self.encoder.patch_u32(0, len as u32);
+
+        self.sink.write_record(self.encoder.bytes())?;
+        self.next_sequence += 1;
+
+        Ok(sequence)
+    }
+
+    #[inline(always)]
+    pub fn flush(&mut self) -> Result<(), JournalError> {
+        Ok(self.sink.flush()?)
+    }
+}
+
+/// Reads records back in order.
+/// Stops at a torn record at the end, left behind by a crash in the middle of a write.
+pub struct JournalReader<'a, T> {
+    decoder: Decoder<'a>,
+    next_sequence: Option<u64>,
+    failed: bool,
+    _order: PhantomData<T>,
+}
+
+impl<'a, T: Order + OrderCodec> JournalReader<'a, T> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            decoder: Decoder::new(bytes),
+            next_sequence: None,
+            failed: false,
+            _order: PhantomData,
+        }
+    }
+
+    // Bytes of the complete records read so far
+    #[inline(always)]
+    pub fn position(&self) -> usize {
+        self.decoder.position()
+    }
+
+    // Sequence the record after the last one read should have
+    #[inline(always)]
+    pub fn next_sequence(&self) -> Option<u64> {
+        self.next_sequence
+    }
+
+    fn read_record(&mut self) -> Result<Option<JournalRecord<T>>, JournalError> {
+        let mut header = self.decoder.clone();
+        let Ok(len) = header.get_u32() else {
+            return Ok(None);
+        };
+
+        let len = len as usize;
+        if !(MIN_RECORD_LEN..=MAX_RECORD_LEN).contains(&len) {
+            return Err(JournalError::InvalidRecordLength {
+                position: self.decoder.position(),
+                len,
+            });
+        }
+
+        // Torn write at the end, unless a complete record follows the corrupted length
+        if header.remaining() < len {
+            let tail = header.take(header.remaining())?;
+            if Self::has_complete_record(tail) {
+                return Err(JournalError::InvalidRecordLength {
+                    position: self.decoder.position(),
+                    len,
+                });
+            }
+
+            return Ok(None);
+        }
+
+        let bytes = header.take(len)?;
+        let (body, checksum) = bytes.split_at(len - RECORD_CHECKSUM_LEN);
+
+        let mut decoder = Decoder::new(body);
+        let sequence = decoder.get_u64()?;
+        if crc32(body) != u32::from_le_bytes(checksum.try_into().unwrap()) {
+            return Err(JournalError::ChecksumMismatch { sequence });
+        }
+
+        if let Some(expected) = self.next_sequence
+            && expected != sequence
+        {
+            return Err(JournalError::SequenceGap {
+                expected,
+                found: sequence,
+            });
+        }
+
+        let timestamp = decoder.get_u64()?;
+        let command = JournalCommand::decode(&mut decoder)?;
+
+        self.decoder = header;
+        self.next_sequence = Some(sequence + 1);
+
+        Ok(Some(JournalRecord {
+            sequence,
+            timestamp,
+            command,
+        }))
+    }
+
+    // A torn write leaves a prefix of the last record only
+    fn has_complete_record(tail: &[u8]) -> bool {
+        (0..tail.len().saturating_sub(4 + MIN_RECORD_LEN - 1)).any(|start| {
+            let len = u32::from_le_bytes(tail[start..start + 4].try_into().unwrap()) as usize;
+            if len < MIN_RECORD_LEN || start + 4 + len > tail.len() {
+                return false;
+            }
+
+            let (body, checksum) =
+                tail[start + 4..start + 4 + len].split_at(len - RECORD_CHECKSUM_LEN);
+            crc32(body) == u32::from_le_bytes(checksum.try_into().unwrap())
+        })
+    }
+}
+
+impl<T: Order + OrderCodec> Iterator for JournalReader<'_, T> {
+    type Item = Result<JournalRecord<T>, JournalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let record = self.read_record().transpose();
+        self.failed = matches!(record, Some(Err(_)));
+
+        record
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JournalRecovery {
+    pub record_count: u64,
+    pub next_sequence: u64, // Sequence to continue the journal with
+    pub valid_len: usize,   // Bytes of complete records, anything after is a torn write to drop
+}
+
+/// Replay the journal into a fresh book, the book clock is set to the time of each record
+/// so fills and accepted orders are stamped as they were the first time
+pub fn recover<T: Order + OrderCodec, L: OrderListener>(
+    bytes: &[u8],
+    book: &mut OrderBook<T, L, ManualClock>,
+) -> Result<JournalRecovery, JournalError> {
+    let mut reader = JournalReader::<T>::new(bytes);
+    let mut record_count = 0;
+
+    for record in reader.by_ref() {
+        let record = record?;
+        book.clock_mut().set(record.timestamp);
+        record.command.apply(book);
+        record_count += 1;
+    }
+
+    Ok(JournalRecovery {
+        record_count,
+        next_sequence: reader.next_sequence().unwrap_or(1),
+        valid_len: reader.position(),
+    })
+}
+
+/// Replay the journal into a book restored from a snapshot, `next_sequence` is the one
+/// returned by `restore`. Records the snapshot already covers are skipped. The snapshot
+/// doesn't carry the instrument spec, the book needs the one in force when it was taken.
+pub fn recover_from<T: Order + OrderCodec, L: OrderListener>(
+    bytes: &[u8],
+    book: &mut OrderBook<T, L, ManualClock>,
+    next_sequence: u64,
+) -> Result<JournalRecovery, JournalError> {
+    let mut reader = JournalReader::<T>::new(bytes);
+    let mut record_count = 0;
+
+    for record in reader.by_ref() {
+        let record = record?;
+        if record.sequence < next_sequence {
+            continue;
+        }
+
+        // Journal has to pick up right where the snapshot ends
+        if record_count == 0 && record.sequence != next_sequence {
+            return Err(JournalError::SequenceGap {
+                expected: next_sequence,
+                found: record.sequence,
+            });
+        }
+
+        book.clock_mut().set(record.timestamp);
+        record.command.apply(book);
+        record_count += 1;
+    }
+
+    // Journal ending before the snapshot lost records the snapshot has
+    let journal_sequence = reader.next_sequence().unwrap_or(next_sequence);
+    if journal_sequence < next_sequence {
+        return Err(JournalError::SequenceGap {
+            expected: next_sequence,
+            found: journal_sequence,
+        });
+    }
+
+    Ok(JournalRecovery {
+        record_count,
+        next_sequence: journal_sequence,
+        valid_len: reader.position(),
+    })
+}
+
+/// Book whose every command is journaled before it's applied.
+/// The book runs on a manual clock set from `clock` before each command,
+/// the same time is journaled so a replay stamps the same times.
+pub struct JournaledBook<T, L, S, C = SystemClock>
+where
+    T: Order + OrderCodec,
+    L: OrderListener,
+    S: JournalSink,
+    C: Clock,
+{
+    book: OrderBook<T, L, ManualClock>,
+    writer: JournalWriter<S>,
+    clock: C,
+}
+
+impl<T, L, S, C> JournaledBook<T, L, S, C>
+where
+    T: Order + OrderCodec,
+    L: OrderListener,
+    S: JournalSink,
+    C: Clock,
+{
+    pub fn new(book: OrderBook<T, L, ManualClock>, writer: JournalWriter<S>, clock: C) -> Self {
+        Self {
+            book,
+            writer,
+            clock,
+        }
+    }
+
+    #[inline(always)]
+    pub fn book(&self) -> &OrderBook<T, L, ManualClock> {
+        &self.book
+    }
+
+    #[inline(always)]
+    pub fn writer(&self) -> &JournalWriter<S> {
+        &self.writer
+    }
+
+    #[inline(always)]
+    pub fn writer_mut(&mut self) -> &mut JournalWriter<S> {
+        &mut self.writer
+    }
+
+    #[inline(always)]
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    #[inline(always)]
+    pub fn clock_mut(&mut self) -> &mut C {
+        &mut self.clock
+    }
+
+    /// Snapshot of the book covering every record journaled so far
+    #[inline(always)]
+    pub fn snapshot(&self) -> Vec<u8> {
+        self.book.snapshot(self.writer.next_sequence())
+    }
+
+    pub fn into_parts(self) -> (OrderBook<T, L, ManualClock>, JournalWriter<S>, C) {
+        (self.book, self.writer, self.clock)
+    }
+
+    pub fn insert_order(&mut self, order: &T) -> Result<Vec<OrderMatch>, JournalError> {
+        self.journal(&JournalCommand::Insert(order.clone()))?;
+        Ok(self.book.insert_order(order)?)
+    }
+
+    pub fn insert_oco_order(
+        &mut self,
+        order: &T,
+        linked_order: &T,
+        mode: OcoMode,
+    ) -> Result<Vec<OrderMatch>, JournalError> {
+        self.journal(&JournalCommand::InsertOco {
+            order: order.clone(),
+            linked_order: linked_order.clone(),
+            mode,
+        })?;
+        Ok(self.book.insert_oco_order(order, linked_order, mode)?)
+    }
+
+    pub fn replace_order_by_id(
+        &mut self,
+        order_id: OrderId,
+        quantity_delta: i64,
+        new_price: Price,
+    ) -> Result<ReplaceOutcome, JournalError> {
+        self.journal(&JournalCommand::Replace {
+            order_id,
+            quantity_delta,
+            new_price,
+        })?;
+        Ok(self
+            .book
+            .replace_order_by_id(order_id, quantity_delta, new_price)?)
+    }
+
+    pub fn cancel_order_by_id(&mut self, order_id: OrderId) -> Result<T, JournalError> {
+        self.journal(&JournalCommand::Cancel(order_id))?;
+        Ok(self.book.cancel_order_by_id(order_id)?)
+    }
+
+    pub fn trigger_stop_orders(&mut self) -> Result<Vec<OrderMatch>, JournalError> {
+        self.journal(&JournalCommand::TriggerStops)?;
+        Ok(self.book.trigger_stop_orders())
+    }
+
+    pub fn set_market_price(&mut self, price: Price) -> Result<(), JournalError> {
+        self.journal(&JournalCommand::SetMarketPrice(price))?;
+        self.book.set_market_price(price);
+
+        Ok(())
+    }
+
+    pub fn advance_time(&mut self, now: Timestamp) -> Result<Vec<T>, JournalError> {
+        self.journal(&JournalCommand::AdvanceTime(now))?;
+        Ok(self.book.advance_time(now))
+    }
+
+    // Expire orders due at the time of the clock
+    pub fn expire_orders(&mut self) -> Result<Vec<T>, JournalError> {
+        self.advance_time(self.clock.now())
+    }
+
+    pub fn set_day_end(&mut self, day_end: Timestamp) -> Result<(), JournalError> {
+        self.journal(&JournalCommand::SetDayEnd(day_end))?;
+        self.book.set_day_end(day_end);
+
+        Ok(())
+    }
+
+    // Trading rules are journaled too, a replay validates orders as they were
+    pub fn set_instrument_spec(
+        &mut self,
+        instrument_spec: InstrumentSpec,
+    ) -> Result<(), JournalError> {
+        self.journal(&JournalCommand::SetInstrumentSpec(instrument_spec))?;
+        self.book.set_instrument_spec(instrument_spec);
+
+        Ok(())
+    }
+
+    // Journal and apply a command coming from elsewhere, results are dropped
+    pub fn apply(&mut self, command: &JournalCommand<T>) -> Result<(), JournalError> {
+        self.journal(command)?;
+        command.apply(&mut self.book);
+
+        Ok(())
+    }
+
+    // Nothing reaches the book when the journal can't take the command
+    #[inline(always)]
+    fn journal(&mut self, command: &JournalCommand<T>) -> Result<u64, JournalError> {
+        let now = self.clock.now();
+        self.book.clock_mut().set(now);
+        self.writer.append(now, command)
+    }
+}
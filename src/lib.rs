@@ -1,3 +1,8 @@
+pub mod clock;
+pub mod codec;
 pub mod core;
+pub mod fix;
+pub mod journal;
+pub mod replay;
 pub mod schedular;
 pub mod utils;
@@ -0,0 +1,445 @@
+use std::fmt::Write;
+
+use thiserror::Error;
+
+use crate::{
+    core::{
+        instrument_spec::InstrumentSpec,
+        order::{
+            ExecutionCondition, MarketProtection, Order, OrderSide, OrderType, PostOnly,
+            SelfTradePrevention, TimeInForce, Timestamp, TrailingOffset,
+        },
+        order_book::OrderBook,
+        order_link::OcoMode,
+        order_spec::OrderSpec,
+    },
+    journal::{JournalCommand, JournalError, JournalReader},
+};
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("Line {line}: {message}")]
+    Parse { line: usize, message: String },
+
+    #[error(transparent)]
+    Journal(#[from] JournalError),
+}
+
+/// One line of a command file, a book command or the time the next commands run at
+#[derive(Debug, Clone)]
+pub enum ReplayStep {
+    Time(Timestamp),
+    Command(JournalCommand<OrderSpec>),
+}
+
+/// Parse a command file, one command per line, `#` starts a comment:
+///
+/// ```text
+/// time 1000
+/// insert 1 sell limit 101 4 tif=GTT expire=5000
+/// insert 2 buy market 3 protect_ticks=2
+/// oco cancel 3 sell limit 105 2 | 4 sell stop 95 2
+/// replace 1 -1 102
+/// cancel 1
+/// trigger
+/// price 100
+/// advance 6000
+/// day_end 9000
+/// spec 5 10 20 1000 50 500 5000
+/// ```
+///
+/// Order kinds are `limit <price> <qty>`, `market <qty>`, `stop <stop> <qty>`,
+/// `stop_limit <stop> <price> <qty>`, `trailing <offset> <qty>` and `trailing_bps <bps> <qty>`,
+/// followed by the options `tif=`, `aon`, `post_only=reject|slide`, `owner=`,
+/// `stp=newest|oldest|both|decrement`, `expire=`, `protect_ticks=` and `protect_bps=`.
+/// `spec` takes the tick size, lot size, min and max quantity, min and max price and min notional.
+pub fn parse_steps(text: &str) -> Result<Vec<ReplayStep>, ReplayError> {
+    let mut steps = Vec::new();
+    for (line_idx, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let step = parse_step(line).map_err(|message| ReplayError::Parse {
+            line: line_idx + 1,
+            message,
+        })?;
+        steps.push(step);
+    }
+
+    Ok(steps)
+}
+
+/// Commands of a binary journal, each one preceded by the time it was applied at
+pub fn journal_steps(bytes: &[u8]) -> Result<Vec<ReplayStep>, ReplayError> {
+    let mut steps = Vec::new();
+    for record in JournalReader::<OrderSpec>::new(bytes) {
+        let record = record?;
+        steps.push(ReplayStep::Time(record.timestamp));
+        steps.push(ReplayStep::Command(record.command));
+    }
+
+    Ok(steps)
+}
+
+/// Drive a fresh book with the steps and write every command followed by the events
+/// it caused, then the book left at the end. Same steps always give the same output.
+pub fn replay(steps: &[ReplayStep]) -> String {
+    let mut book = OrderBook::<OrderSpec>::new(steps.len());
+    let mut output = String::new();
+
+    for step in steps {
+        _ = writeln!(output, "{}", format_step(step));
+
+        let command = match step {
+            ReplayStep::Time(now) => {
+                book.clock_mut().set(*now);
+                continue;
+            }
+            ReplayStep::Command(command) => command,
+        };
+
+        command.apply(&mut book);
+
+        // Setters don't start a new operation, the events are still the ones of the last command
+        if matches!(
+            command,
+            JournalCommand::SetMarketPrice(_) | JournalCommand::SetDayEnd(_)
+        ) {
+            continue;
+        }
+
+        for event in book.events() {
+            _ = writeln!(output, "  {:?}", event);
+        }
+    }
+
+    let depth = book.depth(usize::MAX);
+    _ = writeln!(output, "== book");
+    for level in depth.asks.iter().rev() {
+        _ = writeln!(
+            output,
+            "  ask {} x{} ({})",
+            level.price, level.quantity, level.order_count
+        );
+    }
+    for level in depth.bids.iter() {
+        _ = writeln!(
+            output,
+            "  bid {} x{} ({})",
+            level.price, level.quantity, level.order_count
+        );
+    }
+    _ = writeln!(
+        output,
+        "  market price {} stop quantity {}",
+        book.current_market_price(),
+        book.stop_bids().total_quantity() + book.stop_asks().total_quantity()
+    );
+
+    output
+}
+
+/// First line the outputs differ at, `None` when they are the same
+pub fn diff_lines(expected: &str, actual: &str) -> Option<String> {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+
+    for line in 1.. {
+        match (expected_lines.next(), actual_lines.next()) {
+            (None, None) => return None,
+            (expected, actual) if expected == actual => continue,
+            (expected, actual) => {
+                return Some(format!(
+                    "line {}:\n  expected: {}\n  actual:   {}",
+                    line,
+                    expected.unwrap_or("<end of output>"),
+                    actual.unwrap_or("<end of output>")
+                ));
+            }
+        }
+    }
+
+    None
+}
+
+/// The step in the command file syntax, parsing it back gives the same step
+pub fn format_step(step: &ReplayStep) -> String {
+    let command = match step {
+        ReplayStep::Time(now) => return format!("time {}", now),
+        ReplayStep::Command(command) => command,
+    };
+
+    match command {
+        JournalCommand::Insert(order) => format!("insert {}", format_order(order)),
+        JournalCommand::InsertOco {
+            order,
+            linked_order,
+            mode,
+        } => format!(
+            "oco {} {} | {}",
+            match mode {
+                OcoMode::Cancel => "cancel",
+                OcoMode::Reduce => "reduce",
+            },
+            format_order(order),
+            format_order(linked_order)
+        ),
+        JournalCommand::Replace {
+            order_id,
+            quantity_delta,
+            new_price,
+        } => format!("replace {} {} {}", order_id, quantity_delta, new_price),
+        JournalCommand::Cancel(order_id) => format!("cancel {}", order_id),
+        JournalCommand::TriggerStops => "trigger".to_string(),
+        JournalCommand::SetMarketPrice(price) => format!("price {}", price),
+        JournalCommand::AdvanceTime(now) => format!("advance {}", now),
+        JournalCommand::SetDayEnd(day_end) => format!("day_end {}", day_end),
+        JournalCommand::SetInstrumentSpec(spec) => format!(
+            "spec {} {} {} {} {} {} {}",
+            spec.tick_size,
+            spec.lot_size,
+            spec.min_quantity,
+            spec.max_quantity,
+            spec.min_price,
+            spec.max_price,
+            spec.min_notional
+        ),
+    }
+}
+
+fn format_order(order: &OrderSpec) -> String {
+    let mut text = format!(
+        "{} {} ",
+        order.id,
+        if order.is_buy() { "buy" } else { "sell" }
+    );
+
+    _ = match (order.order_type, order.trailing_offset) {
+        (OrderType::Market, _) => write!(text, "market {}", order.quantity),
+        (OrderType::Limit, _) => write!(text, "limit {} {}", order.price, order.quantity),
+        (OrderType::StopMarket, _) => write!(text, "stop {} {}", order.stop_price, order.quantity),
+        (OrderType::StopLimit, _) => write!(
+            text,
+            "stop_limit {} {} {}",
+            order.stop_price, order.price, order.quantity
+        ),
+        (OrderType::TrailingStop, Some(TrailingOffset::BasisPoints(bps))) => {
+            write!(text, "trailing_bps {} {}", bps, order.quantity)
+        }
+        (OrderType::TrailingStop, offset) => {
+            let offset = match offset {
+                Some(TrailingOffset::Absolute(offset)) => offset,
+                _ => 0,
+            };
+            write!(text, "trailing {} {}", offset, order.quantity)
+        }
+    };
+
+    if order.time_in_force != TimeInForce::GTC {
+        _ = write!(text, " tif={:?}", order.time_in_force);
+    }
+    if order.is_all_or_none() {
+        text.push_str(" aon");
+    }
+    match order.post_only {
+        PostOnly::None => {}
+        PostOnly::Reject => text.push_str(" post_only=reject"),
+        PostOnly::Slide => text.push_str(" post_only=slide"),
+    }
+    if order.owner_id != 0 {
+        _ = write!(text, " owner={}", order.owner_id);
+    }
+    match order.self_trade_prevention {
+        SelfTradePrevention::None => {}
+        SelfTradePrevention::CancelNewest => text.push_str(" stp=newest"),
+        SelfTradePrevention::CancelOldest => text.push_str(" stp=oldest"),
+        SelfTradePrevention::CancelBoth => text.push_str(" stp=both"),
+        SelfTradePrevention::DecrementAndCancel => text.push_str(" stp=decrement"),
+    }
+    if order.expire_time != 0 {
+        _ = write!(text, " expire={}", order.expire_time);
+    }
+    match order.market_protection {
+        MarketProtection::None => {}
+        MarketProtection::Ticks(ticks) => _ = write!(text, " protect_ticks={}", ticks),
+        MarketProtection::BasisPoints(bps) => _ = write!(text, " protect_bps={}", bps),
+    }
+
+    text
+}
+
+fn parse_step(line: &str) -> Result<ReplayStep, String> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let args = &tokens[1..];
+
+    let command = match tokens[0] {
+        "time" => return Ok(ReplayStep::Time(parse_arg(args, 0, "time")?)),
+        "insert" => JournalCommand::Insert(parse_order(args)?),
+        "oco" => {
+            let mode = match args.first() {
+                Some(&"cancel") => OcoMode::Cancel,
+                Some(&"reduce") => OcoMode::Reduce,
+                _ => return Err("oco mode must be cancel or reduce".to_string()),
+            };
+            let Some(split) = args.iter().position(|&token| token == "|") else {
+                return Err("oco legs must be separated by |".to_string());
+            };
+
+            JournalCommand::InsertOco {
+                order: parse_order(&args[1..split])?,
+                linked_order: parse_order(&args[split + 1..])?,
+                mode,
+            }
+        }
+        "replace" => JournalCommand::Replace {
+            order_id: parse_arg(args, 0, "order id")?,
+            quantity_delta: parse_arg(args, 1, "quantity delta")?,
+            new_price: parse_arg(args, 2, "price")?,
+        },
+        "cancel" => JournalCommand::Cancel(parse_arg(args, 0, "order id")?),
+        "trigger" => JournalCommand::TriggerStops,
+        "price" => JournalCommand::SetMarketPrice(parse_arg(args, 0, "price")?),
+        "advance" => JournalCommand::AdvanceTime(parse_arg(args, 0, "time")?),
+        "day_end" => JournalCommand::SetDayEnd(parse_arg(args, 0, "time")?),
+        "spec" => JournalCommand::SetInstrumentSpec(InstrumentSpec {
+            tick_size: parse_arg(args, 0, "tick size")?,
+            lot_size: parse_arg(args, 1, "lot size")?,
+            min_quantity: parse_arg(args, 2, "min quantity")?,
+            max_quantity: parse_arg(args, 3, "max quantity")?,
+            min_price: parse_arg(args, 4, "min price")?,
+            max_price: parse_arg(args, 5, "max price")?,
+            min_notional: parse_arg(args, 6, "min notional")?,
+        }),
+        keyword => return Err(format!("unknown command {}", keyword)),
+    };
+
+    Ok(ReplayStep::Command(command))
+}
+
+fn parse_order(tokens: &[&str]) -> Result<OrderSpec, String> {
+    let id = parse_arg(tokens, 0, "order id")?;
+    let order_side = match tokens.get(1) {
+        Some(&"buy") => OrderSide::Buy,
+        Some(&"sell") => OrderSide::Sell,
+        _ => return Err("order side must be buy or sell".to_string()),
+    };
+
+    let (mut order, options) = match tokens.get(2) {
+        Some(&"limit") => (
+            OrderSpec::limit_price(
+                id,
+                order_side,
+                parse_arg(tokens, 3, "price")?,
+                parse_arg(tokens, 4, "quantity")?,
+            ),
+            &tokens[5..],
+        ),
+        Some(&"market") => (
+            OrderSpec::market(id, order_side, parse_arg(tokens, 3, "quantity")?),
+            &tokens[4..],
+        ),
+        Some(&"stop") => (
+            OrderSpec::stop_market(
+                id,
+                order_side,
+                parse_arg(tokens, 3, "stop price")?,
+                parse_arg(tokens, 4, "quantity")?,
+            ),
+            &tokens[5..],
+        ),
+        Some(&"stop_limit") => (
+            OrderSpec::stop_limit(
+                id,
+                order_side,
+                parse_arg(tokens, 3, "stop price")?,
+                parse_arg(tokens, 4, "price")?,
+                parse_arg(tokens, 5, "quantity")?,
+            ),
+            &tokens[6..],
+        ),
+        Some(&"trailing") => (
+            OrderSpec::trailing_stop(
+                id,
+                order_side,
+                TrailingOffset::Absolute(parse_arg(tokens, 3, "trailing offset")?),
+                parse_arg(tokens, 4, "quantity")?,
+            ),
+            &tokens[5..],
+        ),
+        Some(&"trailing_bps") => (
+            OrderSpec::trailing_stop(
+                id,
+                order_side,
+                TrailingOffset::BasisPoints(parse_arg(tokens, 3, "trailing offset")?),
+                parse_arg(tokens, 4, "quantity")?,
+            ),
+            &tokens[5..],
+        ),
+        _ => return Err("unknown order kind".to_string()),
+    };
+
+    for option in options {
+        let (key, value) = option.split_once('=').unwrap_or((option, ""));
+        match key {
+            "tif" => order.time_in_force = parse_time_in_force(value)?,
+            "aon" => order.execution_condition = ExecutionCondition::AON,
+            "post_only" => {
+                order.post_only = match value {
+                    "reject" => PostOnly::Reject,
+                    "slide" => PostOnly::Slide,
+                    _ => return Err(format!("invalid post_only {}", value)),
+                }
+            }
+            "owner" => order.owner_id = parse_value(value, "owner")?,
+            "stp" => {
+                order.self_trade_prevention = match value {
+                    "newest" => SelfTradePrevention::CancelNewest,
+                    "oldest" => SelfTradePrevention::CancelOldest,
+                    "both" => SelfTradePrevention::CancelBoth,
+                    "decrement" => SelfTradePrevention::DecrementAndCancel,
+                    _ => return Err(format!("invalid stp {}", value)),
+                }
+            }
+            "expire" => order.expire_time = parse_value(value, "expire")?,
+            "protect_ticks" => {
+                order.market_protection =
+                    MarketProtection::Ticks(parse_value(value, "protect_ticks")?)
+            }
+            "protect_bps" => {
+                order.market_protection =
+                    MarketProtection::BasisPoints(parse_value(value, "protect_bps")?)
+            }
+            _ => return Err(format!("unknown option {}", option)),
+        }
+    }
+
+    Ok(order)
+}
+
+fn parse_time_in_force(value: &str) -> Result<TimeInForce, String> {
+    match value {
+        "GTC" => Ok(TimeInForce::GTC),
+        "IOC" => Ok(TimeInForce::IOC),
+        "FOK" => Ok(TimeInForce::FOK),
+        "DAY" => Ok(TimeInForce::DAY),
+        "GTD" => Ok(TimeInForce::GTD),
+        "GTT" => Ok(TimeInForce::GTT),
+        _ => Err(format!("invalid tif {}", value)),
+    }
+}
+
+fn parse_arg<V: std::str::FromStr>(tokens: &[&str], idx: usize, name: &str) -> Result<V, String> {
+    let Some(token) = tokens.get(idx) else {
+        return Err(format!("missing {}", name));
+    };
+
+    parse_value(token, name)
+}
+
+fn parse_value<V: std::str::FromStr>(value: &str, name: &str) -> Result<V, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid {} {}", name, value))
+}
@@ -0,0 +1,59 @@
+use std::collections::BTreeSet;
+
+use crate::core::order::{OrderId, Timestamp};
+
+/// Resting orders by expire time, earliest first.
+/// Entries aren't removed when the order leaves the book, the book checks
+/// the order is still resting with the same expire time once it's due.
+#[derive(Debug, Clone, Default)]
+pub struct ExpiryScheduler {
+    expiries: BTreeSet<(Timestamp, OrderId)>,
+}
+
+impl ExpiryScheduler {
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.expiries.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.expiries.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn schedule(&mut self, expire_time: Timestamp, order_id: OrderId) {
+        self.expiries.insert((expire_time, order_id));
+    }
+
+    // Earliest expire time, if any
+    #[inline(always)]
+    pub fn next_expire_time(&self) -> Option<Timestamp> {
+        self.expiries.first().map(|&(expire_time, _)| expire_time)
+    }
+
+    // Entries due at `now`, by expire time then order id
+    pub fn pop_expired(&mut self, now: Timestamp) -> Vec<(Timestamp, OrderId)> {
+        let mut expired = Vec::new();
+        while let Some(&(expire_time, order_id)) = self.expiries.first() {
+            if expire_time > now {
+                break;
+            }
+
+            self.expiries.pop_first();
+            expired.push((expire_time, order_id));
+        }
+
+        expired
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.expiries.clear();
+    }
+}
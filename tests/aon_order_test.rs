@@ -0,0 +1,173 @@
+#[cfg(test)]
+mod tests {
+    use market_forge::core::{
+        order::{ExecutionCondition, Order, OrderSide, TimeInForce},
+        order_book::OrderBook,
+        order_match::OrderMatch,
+        order_replace::ReplaceOutcome,
+        order_spec::OrderSpec,
+    };
+
+    fn aon(id: u32, order_side: OrderSide, price: u64, quantity: u64) -> OrderSpec {
+        OrderSpec::limit_price(id, order_side, price, quantity)
+            .with_execution_condition(ExecutionCondition::AON)
+    }
+
+    #[test]
+    fn aon_incoming_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 100, 5));
+
+        // Not enough quantity, rest without any partial fill
+        let res = book.insert_order(&aon(2, OrderSide::Buy, 100, 8));
+        assert!(
+            res.unwrap().is_empty(),
+            "aon should not be partially filled"
+        );
+        assert_eq!(book.asks().total_quantity(), 5);
+        assert_eq!(book.bids().total_quantity(), 8);
+
+        // Enough quantity, fill completely
+        let res = book.insert_order(&aon(3, OrderSide::Buy, 100, 5)).unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].quantity, 5);
+        assert_eq!(book.asks().len(), 0);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn aon_resting_not_blocking_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&aon(1, OrderSide::Sell, 100, 10));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 100, 5));
+        _ = book.insert_order(&OrderSpec::limit_price(3, OrderSide::Sell, 101, 5));
+
+        // Skip the aon order and fill the order behind it
+        let res = book
+            .insert_order(&OrderSpec::limit_price(4, OrderSide::Buy, 101, 7))
+            .unwrap();
+        let matches: Vec<_> = res.iter().map(|m| (m.match_to_id, m.quantity)).collect();
+        assert_eq!(matches, vec![(2, 5), (3, 2)]);
+        assert_eq!(book.asks().total_quantity(), 13);
+
+        // Large enough order fill the aon order
+        let res = book
+            .insert_order(&OrderSpec::market(5, OrderSide::Buy, 12))
+            .unwrap();
+        let matches: Vec<_> = res.iter().map(|m| (m.match_to_id, m.quantity)).collect();
+        assert_eq!(matches, vec![(1, 10), (3, 2)]);
+        assert_eq!(book.asks().total_quantity(), 1);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn aon_fill_or_kill_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&aon(1, OrderSide::Sell, 100, 10));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 100, 5));
+
+        // Quantity at the level is 15, but only 5 can be filled
+        let res = book.insert_order(
+            &OrderSpec::limit_price(3, OrderSide::Buy, 100, 8).with_time_in_force(TimeInForce::FOK),
+        );
+        assert!(res.unwrap().is_empty(), "fok should be killed");
+        assert_eq!(book.asks().total_quantity(), 15);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn aon_new_liquidity_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&aon(1, OrderSide::Buy, 100, 10));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 100, 4));
+        assert_eq!(book.bids().total_quantity(), 10);
+        assert_eq!(book.asks().total_quantity(), 4);
+
+        // New liquidity complete the aon order
+        let res = book.insert_order(&OrderSpec::limit_price(3, OrderSide::Sell, 99, 6));
+        assert_eq!(
+            res,
+            Ok(vec![
+                OrderMatch {
+                    order_side: OrderSide::Buy,
+                    price: 99,
+                    quantity: 6,
+                    match_from_id: 1,
+                    match_to_id: 3,
+                    timestamp: 0,
+                },
+                OrderMatch {
+                    order_side: OrderSide::Buy,
+                    price: 100,
+                    quantity: 4,
+                    match_from_id: 1,
+                    match_to_id: 2,
+                    timestamp: 0,
+                },
+            ])
+        );
+
+        assert_eq!(book.bids().len(), 0);
+        assert_eq!(book.asks().len(), 0);
+        assert_eq!(book.order_allocator().len(), 0);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn aon_reduced_in_place_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        // Bid can't fill the larger All-Or-None ask, the book stays crossed
+        _ = book.insert_order(&aon(1, OrderSide::Sell, 100, 10));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 100, 5));
+        assert_eq!(book.bids().total_quantity(), 5);
+
+        // Reduced to what the bid can take, traded right away
+        let res = book.replace_order_by_id(1, -5, 0);
+        assert_eq!(
+            res,
+            Ok(ReplaceOutcome::Filled {
+                matches: vec![OrderMatch {
+                    order_side: OrderSide::Sell,
+                    price: 100,
+                    quantity: 5,
+                    match_from_id: 1,
+                    match_to_id: 2,
+                    timestamp: 0,
+                }]
+            })
+        );
+        assert_eq!(book.asks().len(), 0);
+        assert_eq!(book.bids().len(), 0);
+        assert_eq!(book.order_allocator().len(), 0);
+
+        // Still too large, stays in place
+        _ = book.insert_order(&aon(3, OrderSide::Buy, 100, 10));
+        _ = book.insert_order(&OrderSpec::limit_price(4, OrderSide::Sell, 100, 3));
+        assert_eq!(
+            book.replace_order_by_id(3, -5, 0),
+            Ok(ReplaceOutcome::InPlace { quantity: 5 })
+        );
+        assert_eq!(book.bids().total_quantity(), 5);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+}
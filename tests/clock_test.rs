@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use market_forge::{
+        clock::{Clock, ManualClock, SystemClock},
+        core::{
+            order::{Order, OrderSide, TimeInForce},
+            order_book::OrderBook,
+            order_spec::OrderSpec,
+        },
+    };
+
+    #[test]
+    fn clock_stamp_test() {
+        let mut book = OrderBook::<OrderSpec>::with_clock(100, ManualClock::new(1_000));
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 100, 5));
+        assert_eq!(book.transact_time(), 1_000);
+        assert_eq!(book.get_order(1).unwrap().accept_time(), 1_000);
+
+        book.clock_mut().advance(500);
+        let res = book
+            .insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 100, 2))
+            .unwrap();
+        assert_eq!(res[0].timestamp, 1_500);
+
+        // Resting order keeps the time it was accepted at
+        assert_eq!(book.get_order(1).unwrap().accept_time(), 1_000);
+    }
+
+    #[test]
+    fn clock_expire_orders_test() {
+        let mut book = OrderBook::<OrderSpec>::with_clock(100, ManualClock::new(0));
+
+        _ = book.insert_order(
+            &OrderSpec::limit_price(1, OrderSide::Sell, 100, 5)
+                .with_time_in_force(TimeInForce::GTT)
+                .with_expire_time(1_000),
+        );
+
+        book.clock_mut().set(999);
+        assert!(book.expire_orders().is_empty());
+
+        book.clock_mut().set(1_000);
+        assert_eq!(book.expire_orders().len(), 1);
+        assert_eq!(book.current_time(), 1_000);
+    }
+
+    #[test]
+    fn clock_system_test() {
+        let clock = SystemClock::new();
+
+        let start = clock.now();
+        assert!(start > 0);
+        assert!(clock.now() >= start);
+    }
+}
@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod tests {
+    use market_forge::core::{
+        exchange::{Exchange, ExchangeStats, SymbolConfig, SymbolStats, TradingStatus},
+        instrument_spec::InstrumentSpec,
+        order::{ExecutionCondition, Order, OrderSide, TimeInForce},
+        order_error::OrderError,
+        order_spec::OrderSpec,
+    };
+
+    fn exchange() -> Exchange<OrderSpec> {
+        let mut exchange = Exchange::<OrderSpec>::new(100);
+        _ = exchange.add_symbol(1, SymbolConfig::default());
+        _ = exchange.add_symbol(
+            2,
+            SymbolConfig {
+                instrument_spec: InstrumentSpec {
+                    tick_size: 5,
+                    lot_size: 10,
+                    ..Default::default()
+                },
+                status: TradingStatus::Open,
+            },
+        );
+
+        exchange
+    }
+
+    #[test]
+    fn exchange_routing_test() {
+        let mut exchange = exchange();
+        assert_eq!(
+            exchange.add_symbol(1, SymbolConfig::default()),
+            Err(OrderError::DuplicateSymbol)
+        );
+
+        // Same order id lives independently in each book
+        _ = exchange.insert_order(1, &OrderSpec::limit_price(1, OrderSide::Sell, 100, 5));
+        _ = exchange.insert_order(2, &OrderSpec::limit_price(1, OrderSide::Sell, 100, 20));
+        assert_eq!(exchange.book(2).unwrap().tick_size(), 5);
+
+        let res = exchange.insert_order(1, &OrderSpec::limit_price(2, OrderSide::Buy, 100, 3));
+        assert_eq!(res.unwrap().len(), 1);
+        assert_eq!(exchange.book(1).unwrap().asks().total_quantity(), 2);
+        assert_eq!(exchange.book(2).unwrap().asks().total_quantity(), 20);
+
+        assert_eq!(
+            exchange.insert_order(3, &OrderSpec::limit_price(3, OrderSide::Buy, 100, 3)),
+            Err(OrderError::SymbolNotFound)
+        );
+
+        assert_eq!(
+            exchange.stats(1),
+            Some(&SymbolStats {
+                order_count: 2,
+                trade_count: 1,
+                traded_quantity: 3,
+            })
+        );
+        assert_eq!(
+            exchange.exchange_stats(),
+            ExchangeStats {
+                symbol_count: 2,
+                resting_orders: 2,
+                order_count: 3,
+                trade_count: 1,
+                traded_quantity: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn exchange_config_test() {
+        let mut exchange = exchange();
+
+        assert_eq!(
+            exchange.insert_order(2, &OrderSpec::limit_price(1, OrderSide::Sell, 100, 15)),
+            Err(OrderError::InvalidLotSize)
+        );
+        _ = exchange.insert_order(2, &OrderSpec::limit_price(1, OrderSide::Sell, 100, 20));
+        assert_eq!(
+            exchange.replace_order_by_id(2, 1, -5, 0),
+            Err(OrderError::InvalidLotSize)
+        );
+
+        // Halted symbol only accepts cancels
+        _ = exchange.set_trading_status(2, TradingStatus::Halted);
+        assert_eq!(
+            exchange.insert_order(2, &OrderSpec::limit_price(2, OrderSide::Sell, 100, 10)),
+            Err(OrderError::TradingNotOpen)
+        );
+        assert_eq!(
+            exchange.replace_order_by_id(2, 1, -10, 0),
+            Err(OrderError::TradingNotOpen)
+        );
+        assert!(exchange.cancel_order_by_id(2, 1).is_ok());
+
+        // Other symbols keep trading
+        assert!(
+            exchange
+                .insert_order(1, &OrderSpec::limit_price(2, OrderSide::Sell, 100, 10))
+                .is_ok()
+        );
+
+        assert!(exchange.remove_symbol(2).is_some());
+        assert_eq!(exchange.symbols().collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn exchange_replace_stats_test() {
+        let mut exchange = exchange();
+
+        _ = exchange.insert_order(
+            1,
+            &OrderSpec::limit_price(1, OrderSide::Sell, 100, 10)
+                .with_execution_condition(ExecutionCondition::AON),
+        );
+        _ = exchange.insert_order(1, &OrderSpec::limit_price(2, OrderSide::Buy, 100, 6));
+
+        // Reduced All-Or-None order trades, the trade counts toward the symbol
+        assert!(exchange.replace_order_by_id(1, 1, -4, 0).is_ok());
+        assert_eq!(
+            exchange.stats(1),
+            Some(&SymbolStats {
+                order_count: 2,
+                trade_count: 1,
+                traded_quantity: 6,
+            })
+        );
+    }
+
+    #[test]
+    fn exchange_expiry_test() {
+        let mut exchange = exchange();
+
+        _ = exchange.insert_order(
+            1,
+            &OrderSpec::limit_price(1, OrderSide::Sell, 100, 5)
+                .with_time_in_force(TimeInForce::DAY),
+        );
+        assert!(exchange.set_day_end(1, 1_000).is_ok());
+
+        // Expiry goes on while the symbol is halted
+        _ = exchange.set_trading_status(1, TradingStatus::Halted);
+        assert_eq!(exchange.advance_time(1, 1_000).unwrap().len(), 1);
+        assert_eq!(exchange.book(1).unwrap().order_allocator().len(), 0);
+        assert_eq!(
+            exchange.advance_time(3, 1_000).err(),
+            Some(OrderError::SymbolNotFound)
+        );
+    }
+}
@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod tests {
+    use market_forge::core::{
+        order::{Order, OrderSide, TimeInForce},
+        order_book::OrderBook,
+        order_error::OrderError,
+        order_event::{CancelReason, OrderEvent},
+        order_spec::OrderSpec,
+    };
+
+    fn expired(order_id: u32, quantity: u64) -> OrderEvent {
+        OrderEvent::Cancelled {
+            order_id,
+            quantity,
+            reason: CancelReason::Expired,
+        }
+    }
+
+    #[test]
+    fn expiry_good_till_time_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(
+            &OrderSpec::limit_price(1, OrderSide::Sell, 100, 5)
+                .with_time_in_force(TimeInForce::GTT)
+                .with_expire_time(2_000),
+        );
+        _ = book.insert_order(
+            &OrderSpec::limit_price(2, OrderSide::Sell, 101, 5)
+                .with_time_in_force(TimeInForce::GTD)
+                .with_expire_time(1_000),
+        );
+        _ = book.insert_order(
+            &OrderSpec::stop_market(3, OrderSide::Buy, 120, 5)
+                .with_time_in_force(TimeInForce::GTT)
+                .with_expire_time(1_000),
+        );
+        _ = book.insert_order(&OrderSpec::limit_price(4, OrderSide::Sell, 102, 5));
+
+        assert!(book.advance_time(999).is_empty());
+        assert!(book.events().is_empty());
+
+        // Expired by expire time then order id, stop orders included
+        let orders = book.advance_time(1_000);
+        assert_eq!(
+            orders.iter().map(|o| o.id()).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(book.events(), &[expired(2, 5), expired(3, 5)]);
+
+        // Time never goes backward
+        assert!(book.advance_time(10).is_empty());
+        assert_eq!(book.current_time(), 1_000);
+
+        let orders = book.advance_time(5_000);
+        assert_eq!(orders.len(), 1);
+        assert_eq!(book.asks().total_quantity(), 5);
+        assert_eq!(book.order_allocator().len(), 1);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn expiry_day_order_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        let day_order =
+            OrderSpec::limit_price(1, OrderSide::Buy, 100, 5).with_time_in_force(TimeInForce::DAY);
+        book.set_day_end(86_400);
+        assert!(book.insert_order(&day_order).is_ok());
+        assert_eq!(book.get_order(1).unwrap().expire_time(), 86_400);
+
+        book.advance_time(100);
+        let past_order = OrderSpec::limit_price(2, OrderSide::Buy, 100, 5)
+            .with_time_in_force(TimeInForce::GTD)
+            .with_expire_time(100);
+        assert_eq!(
+            book.insert_order(&past_order),
+            Err(OrderError::InvalidExpireTime)
+        );
+
+        assert_eq!(book.advance_time(86_400).len(), 1);
+        assert_eq!(book.bids().len(), 0);
+    }
+
+    #[test]
+    fn expiry_day_order_without_day_end_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        // No end of day to expire at, the order stays until cancelled
+        let day_order =
+            OrderSpec::limit_price(1, OrderSide::Buy, 100, 5).with_time_in_force(TimeInForce::DAY);
+        assert!(book.insert_order(&day_order).is_ok());
+        assert_eq!(book.get_order(1).unwrap().expire_time(), 0);
+
+        assert!(book.advance_time(86_400).is_empty());
+        assert_eq!(book.bids().total_quantity(), 5);
+    }
+
+    #[test]
+    fn expiry_day_end_set_later_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        let day_order =
+            OrderSpec::limit_price(1, OrderSide::Buy, 100, 5).with_time_in_force(TimeInForce::DAY);
+        assert!(book.insert_order(&day_order).is_ok());
+
+        // Resting DAY order picks up the day end once it's set
+        book.set_day_end(1_000);
+        assert_eq!(book.get_order(1).unwrap().expire_time(), 1_000);
+
+        assert_eq!(book.advance_time(1_000).len(), 1);
+        assert_eq!(book.bids().total_quantity(), 0);
+        assert_eq!(book.order_allocator().len(), 0);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn expiry_left_book_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(
+            &OrderSpec::limit_price(1, OrderSide::Sell, 100, 5)
+                .with_time_in_force(TimeInForce::GTT)
+                .with_expire_time(1_000),
+        );
+
+        // Filled order leaves the book, its id is reused with a later expire time
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 100, 5));
+        _ = book.insert_order(
+            &OrderSpec::limit_price(1, OrderSide::Sell, 100, 5)
+                .with_time_in_force(TimeInForce::GTT)
+                .with_expire_time(2_000),
+        );
+
+        assert!(book.advance_time(1_000).is_empty());
+        assert_eq!(book.advance_time(2_000).len(), 1);
+        assert_eq!(book.order_allocator().len(), 0);
+    }
+}
@@ -0,0 +1,370 @@
+#[cfg(test)]
+mod tests {
+    use market_forge::{
+        clock::ManualClock,
+        core::{
+            order::{ExecutionCondition, Order, OrderSide, OrderType, TimeInForce},
+            order_book::OrderBook,
+        },
+        fix::{
+            FixCommand, FixError, FixMessage, FixSession, format_utc_timestamp,
+            parse_utc_timestamp, tag,
+        },
+    };
+
+    fn order_message(msg_type: &str, fields: &[(u32, &str)]) -> Vec<u8> {
+        let mut message = FixMessage::new(msg_type)
+            .with(tag::SENDER_COMP_ID, "CLIENT")
+            .with(tag::TARGET_COMP_ID, "FORGE");
+        for (tag, value) in fields {
+            message.push(*tag, value);
+        }
+        message.encode()
+    }
+
+    fn parse_command(msg_type: &str, fields: &[(u32, &str)]) -> Result<FixCommand, FixError> {
+        FixCommand::parse(&FixMessage::parse(&order_message(msg_type, fields))?)
+    }
+
+    #[test]
+    fn fix_message_test() {
+        let bytes = order_message("D", &[(tag::CL_ORD_ID, "1")]);
+        let message = FixMessage::parse(&bytes).unwrap();
+        assert_eq!(message.msg_type(), "D");
+        assert_eq!(message.get(tag::SENDER_COMP_ID), Some("CLIENT"));
+        assert_eq!(message.get(tag::CL_ORD_ID), Some("1"));
+        assert_eq!(message.encode(), bytes);
+
+        // Heartbeat
+        let bytes = b"8=FIX.4.4\x019=5\x0135=0\x0110=163\x01";
+        assert_eq!(FixMessage::parse(bytes).unwrap().msg_type(), "0");
+
+        let mut corrupted = bytes.to_vec();
+        corrupted[14] = b'1';
+        assert_eq!(
+            FixMessage::parse(&corrupted),
+            Err(FixError::InvalidCheckSum)
+        );
+        assert_eq!(
+            FixMessage::parse(b"8=FIX.4.4\x019=6\x0135=0\x0110=164\x01"),
+            Err(FixError::InvalidBodyLength)
+        );
+        assert_eq!(
+            FixMessage::parse(b"8=FIX.4.2\x019=5\x0135=0\x0110=161\x01"),
+            Err(FixError::InvalidBeginString)
+        );
+        assert_eq!(
+            FixMessage::parse(b"8=FIX.4.4\x019=5\x0135=0"),
+            Err(FixError::Malformed)
+        );
+    }
+
+    #[test]
+    fn fix_command_test() {
+        let FixCommand::NewOrder(order) = parse_command(
+            "D",
+            &[
+                (tag::CL_ORD_ID, "7"),
+                (tag::SIDE, "2"),
+                (tag::ORDER_QTY, "10"),
+                (tag::ORD_TYPE, "4"),
+                (tag::PRICE, "99"),
+                (tag::STOP_PX, "101"),
+                (tag::TIME_IN_FORCE, "1"),
+                (tag::EXEC_INST, "0 G"),
+                (tag::ACCOUNT, "3"),
+            ],
+        )
+        .unwrap() else {
+            panic!("expected new order");
+        };
+        assert_eq!(order.id(), 7);
+        assert_eq!(order.order_side(), OrderSide::Sell);
+        assert_eq!(order.order_type(), OrderType::StopLimit);
+        assert_eq!(order.price(), 99);
+        assert_eq!(order.stop_price(), 101);
+        assert_eq!(order.quantity(), 10);
+        assert_eq!(order.time_in_force(), TimeInForce::GTC);
+        assert_eq!(order.execution_condition(), ExecutionCondition::AON);
+        assert_eq!(order.owner_id(), 3);
+
+        // GTD ends with the expire date, expire time makes it good till time
+        let gtd = [
+            (tag::CL_ORD_ID, "8"),
+            (tag::SIDE, "1"),
+            (tag::ORDER_QTY, "5"),
+            (tag::ORD_TYPE, "2"),
+            (tag::PRICE, "100"),
+            (tag::TIME_IN_FORCE, "6"),
+            (tag::EXPIRE_DATE, "20240102"),
+        ];
+        let Ok(FixCommand::NewOrder(order)) = parse_command("D", &gtd) else {
+            panic!("expected new order");
+        };
+        assert_eq!(order.time_in_force(), TimeInForce::GTD);
+        assert_eq!(
+            order.expire_time(),
+            parse_utc_timestamp("20240103-00:00:00").unwrap()
+        );
+
+        let mut gtt = gtd.to_vec();
+        gtt.push((tag::EXPIRE_TIME, "20240102-12:30:00.250"));
+        let Ok(FixCommand::NewOrder(order)) = parse_command("D", &gtt) else {
+            panic!("expected new order");
+        };
+        assert_eq!(order.time_in_force(), TimeInForce::GTT);
+        assert_eq!(
+            format_utc_timestamp(order.expire_time()),
+            "20240102-12:30:00.250"
+        );
+
+        assert!(matches!(
+            parse_command("F", &[(tag::ORIG_CL_ORD_ID, "7"), (tag::CL_ORD_ID, "9")]),
+            Ok(FixCommand::Cancel { order_id: 7, cl_ord_id }) if cl_ord_id == "9"
+        ));
+        let replace = [
+            (tag::CL_ORD_ID, "A-10"),
+            (tag::ORIG_CL_ORD_ID, "7"),
+            (tag::ORDER_QTY, "4"),
+        ];
+        assert!(matches!(
+            parse_command("G", &replace),
+            Ok(FixCommand::Replace {
+                order_id: 7,
+                cl_ord_id,
+                quantity: 4,
+                price: 0
+            }) if cl_ord_id == "A-10"
+        ));
+
+        // Prices are whole book prices
+        let mut replace = replace.to_vec();
+        replace.push((tag::PRICE, "101.5"));
+        assert_eq!(
+            parse_command("G", &replace).unwrap_err(),
+            FixError::InvalidValue(tag::PRICE, "101.5".to_string())
+        );
+        assert_eq!(
+            parse_command("F", &[(tag::ORIG_CL_ORD_ID, "7")]).unwrap_err(),
+            FixError::MissingTag(tag::CL_ORD_ID)
+        );
+
+        assert_eq!(
+            parse_command("D", &gtd[..4]).unwrap_err(),
+            FixError::MissingTag(tag::PRICE)
+        );
+        assert_eq!(
+            parse_command("D", &[(tag::SIDE, "3")]).unwrap_err(),
+            FixError::MissingTag(tag::CL_ORD_ID)
+        );
+        assert_eq!(
+            parse_command(
+                "D",
+                &[
+                    (tag::CL_ORD_ID, "1"),
+                    (tag::ORDER_QTY, "1"),
+                    (tag::SIDE, "1"),
+                    (tag::ORD_TYPE, "P")
+                ]
+            )
+            .unwrap_err(),
+            FixError::InvalidValue(tag::ORD_TYPE, "P".to_string())
+        );
+        assert_eq!(
+            parse_command("A", &[]).unwrap_err(),
+            FixError::UnsupportedMsgType("A".to_string())
+        );
+    }
+
+    #[test]
+    fn fix_session_test() {
+        let mut book = OrderBook::with_clock(100, ManualClock::new(1_000_000_000));
+        let mut session = FixSession::new("FORGE", "CLIENT");
+
+        // Reports go through the wire and back
+        let mut handle = |book: &mut OrderBook<_, _, _>, msg_type: &str, fields: &[(u32, &str)]| {
+            session
+                .handle(book, &order_message(msg_type, fields))
+                .unwrap()
+                .iter()
+                .map(|message| FixMessage::parse(&message.encode()).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let values = |message: &FixMessage, tags: &[u32]| {
+            tags.iter()
+                .map(|tag| message.get(*tag).unwrap_or("-"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let report_tags = [
+            tag::MSG_TYPE,
+            tag::ORDER_ID,
+            tag::EXEC_TYPE,
+            tag::ORD_STATUS,
+            tag::LEAVES_QTY,
+            tag::CUM_QTY,
+            tag::AVG_PX,
+            tag::LAST_PX,
+            tag::LAST_QTY,
+        ];
+
+        let reports = handle(
+            &mut book,
+            "D",
+            &[
+                (tag::CL_ORD_ID, "1"),
+                (tag::SIDE, "2"),
+                (tag::ORDER_QTY, "10"),
+                (tag::ORD_TYPE, "2"),
+                (tag::PRICE, "101"),
+                (tag::TIME_IN_FORCE, "1"),
+            ],
+        );
+        assert_eq!(reports.len(), 1);
+        assert_eq!(
+            values(
+                &reports[0],
+                &[
+                    tag::SENDER_COMP_ID,
+                    tag::TARGET_COMP_ID,
+                    tag::MSG_SEQ_NUM,
+                    tag::SIDE,
+                    tag::TRANSACT_TIME
+                ]
+            ),
+            "FORGE CLIENT 1 2 19700101-00:00:01.000"
+        );
+        assert_eq!(values(&reports[0], &report_tags), "8 1 0 0 10 0 0 - -");
+
+        let reports = handle(
+            &mut book,
+            "D",
+            &[
+                (tag::CL_ORD_ID, "2"),
+                (tag::SIDE, "1"),
+                (tag::ORDER_QTY, "4"),
+                (tag::ORD_TYPE, "1"),
+                (tag::TIME_IN_FORCE, "3"),
+            ],
+        );
+        let reports: Vec<_> = reports
+            .iter()
+            .map(|report| values(report, &report_tags))
+            .collect();
+        assert_eq!(
+            reports,
+            [
+                "8 2 0 0 4 0 0 - -",
+                "8 2 F 2 0 4 101 101 4",
+                "8 1 F 1 6 4 101 101 4"
+            ]
+        );
+
+        // Order quantity is the total, the filled 4 count against it
+        let reports = handle(
+            &mut book,
+            "G",
+            &[
+                (tag::CL_ORD_ID, "3"),
+                (tag::ORIG_CL_ORD_ID, "1"),
+                (tag::ORDER_QTY, "10"),
+                (tag::PRICE, "102"),
+            ],
+        );
+        assert_eq!(values(&reports[0], &report_tags), "8 1 5 1 6 4 101 - -");
+        assert_eq!(
+            values(&reports[0], &[tag::CL_ORD_ID, tag::ORIG_CL_ORD_ID]),
+            "3 1"
+        );
+        assert_eq!(book.get_order(1).unwrap().quantity(), 6);
+        assert_eq!(book.get_order(1).unwrap().price(), 102);
+
+        // Keep the price
+        let reports = handle(
+            &mut book,
+            "G",
+            &[
+                (tag::CL_ORD_ID, "3"),
+                (tag::ORIG_CL_ORD_ID, "1"),
+                (tag::ORDER_QTY, "12"),
+            ],
+        );
+        assert_eq!(values(&reports[0], &report_tags), "8 1 5 1 8 4 101 - -");
+        assert_eq!(book.get_order(1).unwrap().quantity(), 8);
+        assert_eq!(book.get_order(1).unwrap().price(), 102);
+
+        // Nothing left to trade at or below the filled quantity
+        let reports = handle(
+            &mut book,
+            "G",
+            &[
+                (tag::CL_ORD_ID, "3"),
+                (tag::ORIG_CL_ORD_ID, "1"),
+                (tag::ORDER_QTY, "4"),
+            ],
+        );
+        assert_eq!(
+            values(
+                &reports[0],
+                &[
+                    tag::MSG_TYPE,
+                    tag::ORD_STATUS,
+                    tag::CXL_REJ_RESPONSE_TO,
+                    tag::CXL_REJ_REASON
+                ]
+            ),
+            "9 1 2 99"
+        );
+        assert_eq!(book.get_order(1).unwrap().quantity(), 8);
+
+        let reports = handle(
+            &mut book,
+            "F",
+            &[(tag::CL_ORD_ID, "4"), (tag::ORIG_CL_ORD_ID, "1")],
+        );
+        assert_eq!(values(&reports[0], &report_tags), "8 1 4 4 0 4 101 - -");
+        assert_eq!(
+            values(&reports[0], &[tag::CL_ORD_ID, tag::ORIG_CL_ORD_ID]),
+            "4 1"
+        );
+        assert!(book.get_order(1).is_none());
+
+        // Unknown order is a cancel reject
+        let reports = handle(
+            &mut book,
+            "F",
+            &[(tag::CL_ORD_ID, "5"), (tag::ORIG_CL_ORD_ID, "1")],
+        );
+        assert_eq!(
+            values(
+                &reports[0],
+                &[
+                    tag::MSG_TYPE,
+                    tag::CL_ORD_ID,
+                    tag::ORIG_CL_ORD_ID,
+                    tag::ORD_STATUS,
+                    tag::CXL_REJ_RESPONSE_TO,
+                    tag::CXL_REJ_REASON
+                ]
+            ),
+            "9 5 1 8 1 1"
+        );
+        assert!(reports[0].get(tag::TEXT).is_some());
+
+        // Rejected new order
+        let reports = handle(
+            &mut book,
+            "D",
+            &[
+                (tag::CL_ORD_ID, "6"),
+                (tag::SIDE, "1"),
+                (tag::ORDER_QTY, "0"),
+                (tag::ORD_TYPE, "2"),
+                (tag::PRICE, "100"),
+            ],
+        );
+        assert_eq!(values(&reports[0], &report_tags), "8 6 8 8 0 0 0 - -");
+        assert!(reports[0].get(tag::TEXT).is_some());
+        assert_eq!(session.msg_seq_num(), 10);
+    }
+}
@@ -0,0 +1,23 @@
+# Stops, OCO, all-or-none, post-only, self-trade prevention and expiry
+time 1000
+day_end 9000
+insert 1 sell limit 105 5
+insert 2 sell limit 106 5 owner=7
+insert 3 buy stop 105 2
+insert 4 buy limit 104 3 post_only=slide
+insert 5 buy limit 106 2 post_only=reject
+insert 6 buy limit 106 4 owner=7 stp=newest
+oco cancel 7 sell limit 110 3 | 8 sell stop 100 3
+insert 9 buy limit 103 6 aon
+insert 10 sell limit 103 2
+insert 11 sell limit 103 4
+insert 12 buy limit 101 2 tif=DAY
+insert 13 buy limit 100 2 tif=GTT expire=5000
+insert 14 sell trailing 2 3
+insert 15 buy limit 105 1
+price 104
+trigger
+time 6000
+advance 6000
+insert 16 buy market 20 protect_ticks=1
+advance 9000
@@ -0,0 +1,13 @@
+# Price-time priority, partial fills and cancels
+time 1000
+insert 1 sell limit 101 4
+insert 2 sell limit 101 2
+insert 3 sell limit 102 5
+insert 4 buy limit 99 3
+insert 5 buy limit 102 7
+replace 3 -1 103
+insert 6 buy market 10
+cancel 4
+cancel 4
+insert 7 sell limit 98 2 tif=IOC
+insert 8 sell limit 97 2 tif=FOK
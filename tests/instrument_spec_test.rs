@@ -0,0 +1,181 @@
+#[cfg(test)]
+mod tests {
+    use market_forge::core::{
+        instrument_spec::InstrumentSpec,
+        order::{Order, OrderSide, PostOnly},
+        order_book::OrderBook,
+        order_error::OrderError,
+        order_event::OrderEvent,
+        order_link::OcoMode,
+        order_spec::OrderSpec,
+    };
+
+    fn book() -> OrderBook<OrderSpec> {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+        book.set_instrument_spec(InstrumentSpec {
+            tick_size: 5,
+            lot_size: 10,
+            min_quantity: 20,
+            max_quantity: 1_000,
+            min_price: 50,
+            max_price: 500,
+            min_notional: 5_000,
+        });
+
+        book
+    }
+
+    #[test]
+    fn instrument_spec_insert_test() {
+        let mut book = book();
+
+        let cases = [
+            (
+                OrderSpec::limit_price(1, OrderSide::Buy, 100, 0),
+                OrderError::InvalidQuantity,
+            ),
+            (
+                OrderSpec::limit_price(1, OrderSide::Buy, 100, 25),
+                OrderError::InvalidLotSize,
+            ),
+            (
+                OrderSpec::limit_price(1, OrderSide::Buy, 100, 10),
+                OrderError::QuantityBelowMinimum,
+            ),
+            (
+                OrderSpec::limit_price(1, OrderSide::Buy, 100, 2_000),
+                OrderError::QuantityAboveMaximum,
+            ),
+            (
+                OrderSpec::limit_price(1, OrderSide::Buy, 0, 100),
+                OrderError::InvalidPrice,
+            ),
+            (
+                OrderSpec::limit_price(1, OrderSide::Buy, 102, 100),
+                OrderError::InvalidTickSize,
+            ),
+            (
+                OrderSpec::limit_price(1, OrderSide::Buy, 45, 200),
+                OrderError::PriceBelowMinimum,
+            ),
+            (
+                OrderSpec::limit_price(1, OrderSide::Buy, 505, 20),
+                OrderError::PriceAboveMaximum,
+            ),
+            (
+                OrderSpec::limit_price(1, OrderSide::Buy, 100, 40),
+                OrderError::NotionalBelowMinimum,
+            ),
+            (
+                OrderSpec::stop_market(1, OrderSide::Buy, 103, 100),
+                OrderError::InvalidTickSize,
+            ),
+            (
+                OrderSpec::market(1, OrderSide::Buy, 15),
+                OrderError::InvalidLotSize,
+            ),
+        ];
+
+        for (order, err) in cases {
+            assert_eq!(book.insert_order(&order), Err(err.clone()));
+            assert_eq!(
+                book.events(),
+                &[OrderEvent::Rejected {
+                    order_id: 1,
+                    reason: err,
+                }]
+            );
+        }
+
+        assert_eq!(book.order_allocator().len(), 0);
+
+        // Market order has no price to check
+        assert!(
+            book.insert_order(&OrderSpec::market(1, OrderSide::Buy, 20))
+                .is_ok()
+        );
+        assert!(
+            book.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 100, 50))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn instrument_spec_replace_test() {
+        let mut book = book();
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 100, 100));
+
+        assert_eq!(
+            book.replace_order_by_id(1, -5, 0),
+            Err(OrderError::InvalidLotSize)
+        );
+        assert_eq!(
+            book.replace_order_by_id(1, -90, 0),
+            Err(OrderError::QuantityBelowMinimum)
+        );
+        assert_eq!(
+            book.replace_order_by_id(1, 0, 101),
+            Err(OrderError::InvalidTickSize)
+        );
+        assert_eq!(
+            book.replace_order_by_id(1, -50, 95),
+            Err(OrderError::NotionalBelowMinimum)
+        );
+
+        // Rejected replace leaves the order untouched
+        assert_eq!(book.get_order(1).unwrap().quantity(), 100);
+        assert!(book.replace_order_by_id(1, -50, 105).is_ok());
+    }
+
+    #[test]
+    fn instrument_spec_oco_test() {
+        let mut book = book();
+
+        // Invalid linked leg rejects the pair before the first leg trades
+        let res = book.insert_oco_order(
+            &OrderSpec::limit_price(1, OrderSide::Sell, 100, 100),
+            &OrderSpec::stop_market(2, OrderSide::Sell, 0, 100),
+            OcoMode::Cancel,
+        );
+        assert_eq!(res, Err(OrderError::InvalidPrice));
+        assert_eq!(book.order_allocator().len(), 0);
+    }
+
+    #[test]
+    fn instrument_spec_post_only_slide_test() {
+        let mut book = book();
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 50, 100));
+
+        // Sliding one tick below the best ask goes below the minimum price
+        let res = book.insert_order(
+            &OrderSpec::limit_price(3, OrderSide::Buy, 60, 100).with_post_only(PostOnly::Slide),
+        );
+        assert_eq!(res, Err(OrderError::PriceBelowMinimum));
+
+        // Slid price is too low for the notional
+        _ = book.cancel_order_by_id(1);
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 95, 100));
+        let res = book.insert_order(
+            &OrderSpec::limit_price(3, OrderSide::Buy, 100, 50).with_post_only(PostOnly::Slide),
+        );
+        assert_eq!(res, Err(OrderError::NotionalBelowMinimum));
+        assert_eq!(
+            book.events(),
+            &[OrderEvent::Rejected {
+                order_id: 3,
+                reason: OrderError::NotionalBelowMinimum,
+            }]
+        );
+        assert_eq!(book.bids().len(), 0);
+        assert_eq!(book.asks().total_quantity(), 100);
+
+        // Valid slid price rests one tick below the best ask
+        let res = book.insert_order(
+            &OrderSpec::limit_price(3, OrderSide::Buy, 100, 100).with_post_only(PostOnly::Slide),
+        );
+        assert!(res.unwrap().is_empty());
+        assert_eq!(book.get_order(3).unwrap().price(), 90);
+    }
+}
@@ -0,0 +1,299 @@
+#[cfg(test)]
+mod tests {
+    use fake::{
+        Rng,
+        rand::{SeedableRng, rngs::StdRng},
+    };
+    use market_forge::{
+        clock::{Clock, ManualClock},
+        core::{
+            instrument_spec::InstrumentSpec,
+            order::{Order, OrderId, OrderSide, Price, Quantity, TimeInForce, Timestamp},
+            order_book::OrderBook,
+            order_link::OcoMode,
+            order_spec::OrderSpec,
+        },
+        journal::{
+            JournalCommand, JournalError, JournalReader, JournalWriter, JournaledBook, recover,
+            recover_from,
+        },
+    };
+
+    type BookState = Vec<(Price, OrderId, Quantity, Timestamp)>;
+
+    fn book_state(book: &OrderBook<OrderSpec>) -> (BookState, BookState, Vec<OrderId>, Price) {
+        let resting = |order: &OrderSpec| (order.id(), order.quantity(), order.accept_time());
+        let bids = book
+            .bid_orders()
+            .map(|item| {
+                let (id, quantity, accept_time) = resting(item.order);
+                (item.price, id, quantity, accept_time)
+            })
+            .collect();
+        let asks = book
+            .ask_orders()
+            .map(|item| {
+                let (id, quantity, accept_time) = resting(item.order);
+                (item.price, id, quantity, accept_time)
+            })
+            .collect();
+
+        let mut stops: Vec<OrderId> = book
+            .order_allocator()
+            .iter()
+            .filter(|(_, order)| order.is_stop())
+            .map(|(_, order)| order.id())
+            .collect();
+        stops.sort();
+
+        (bids, asks, stops, book.current_market_price())
+    }
+
+    fn journaled_book() -> JournaledBook<OrderSpec, (), Vec<u8>, ManualClock> {
+        JournaledBook::new(
+            OrderBook::new(10_000),
+            JournalWriter::new(Vec::new()),
+            ManualClock::new(1_000),
+        )
+    }
+
+    #[test]
+    fn journal_recover_test() {
+        let mut journaled = journaled_book();
+        let mut rng = StdRng::seed_from_u64(21);
+
+        for i in 0..5_000 {
+            let order_id = i as OrderId;
+            let order_side = if rng.random_bool(0.5) {
+                OrderSide::Buy
+            } else {
+                OrderSide::Sell
+            };
+            let price = rng.random_range(90..110) as Price;
+            let quantity = rng.random_range(1..10) as Quantity;
+            journaled.clock_mut().advance(rng.random_range(1..100));
+
+            _ = match rng.random_range(0..12) {
+                0 => journaled
+                    .insert_order(&OrderSpec::market(order_id, order_side, quantity))
+                    .map(|_| ()),
+                1 => journaled
+                    .insert_order(&OrderSpec::stop_market(
+                        order_id, order_side, price, quantity,
+                    ))
+                    .map(|_| ()),
+                2 | 3 => journaled
+                    .cancel_order_by_id(rng.random_range(0..order_id + 1))
+                    .map(|_| ()),
+                4 => journaled
+                    .replace_order_by_id(rng.random_range(0..order_id + 1), -1, price)
+                    .map(|_| ()),
+                5 => journaled.set_market_price(price),
+                6 => journaled.trigger_stop_orders().map(|_| ()),
+                7 => journaled
+                    .insert_oco_order(
+                        &OrderSpec::limit_price(order_id, order_side, price, quantity),
+                        &OrderSpec::stop_market(
+                            order_id + 100_000,
+                            order_side.opposite(),
+                            price,
+                            quantity,
+                        ),
+                        OcoMode::Cancel,
+                    )
+                    .map(|_| ()),
+                8 => {
+                    let expire_time = journaled.clock().now() + 50;
+                    journaled
+                        .insert_order(
+                            &OrderSpec::limit_price(order_id, order_side, price, quantity)
+                                .with_time_in_force(TimeInForce::GTT)
+                                .with_expire_time(expire_time),
+                        )
+                        .map(|_| ())
+                }
+                9 => journaled.expire_orders().map(|_| ()),
+                _ => journaled
+                    .insert_order(&OrderSpec::limit_price(
+                        order_id, order_side, price, quantity,
+                    ))
+                    .map(|_| ()),
+            };
+        }
+
+        let (book, writer, _) = journaled.into_parts();
+        assert_eq!(writer.next_sequence(), 5_001);
+
+        let mut recovered = OrderBook::<OrderSpec>::new(10_000);
+        let recovery = recover(writer.sink(), &mut recovered).unwrap();
+        assert_eq!(recovery.record_count, 5_000);
+        assert_eq!(recovery.next_sequence, 5_001);
+        assert_eq!(recovery.valid_len, writer.sink().len());
+
+        assert!(recovered.validate_cache().is_ok());
+        assert_eq!(book_state(&recovered), book_state(&book));
+    }
+
+    #[test]
+    fn journal_torn_write_test() {
+        let mut journaled = journaled_book();
+        _ = journaled.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 101, 4));
+        _ = journaled.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 102, 2));
+        let (_, writer, _) = journaled.into_parts();
+
+        // Crash in the middle of the second record
+        let mut bytes = writer.into_sink();
+        bytes.truncate(bytes.len() - 3);
+
+        let mut book = OrderBook::<OrderSpec>::new(100);
+        let recovery = recover(&bytes, &mut book).unwrap();
+        assert_eq!(recovery.record_count, 1);
+        assert_eq!(recovery.next_sequence, 2);
+        assert!(book.get_order(1).is_some());
+        assert!(book.get_order(2).is_none());
+
+        // Journal continues after the last complete record
+        bytes.truncate(recovery.valid_len);
+        let mut journaled = JournaledBook::new(
+            book,
+            JournalWriter::with_sequence(bytes, 2),
+            ManualClock::new(0),
+        );
+        _ = journaled.cancel_order_by_id(1);
+
+        let records: Vec<u64> = JournalReader::<OrderSpec>::new(journaled.writer().sink())
+            .map(|record| record.unwrap().sequence)
+            .collect();
+        assert_eq!(records, vec![1, 2]);
+    }
+
+    #[test]
+    fn journal_checksum_test() {
+        let mut writer = JournalWriter::new(Vec::new());
+        writer
+            .append(10, &JournalCommand::<OrderSpec>::Cancel(7))
+            .unwrap();
+        writer
+            .append(20, &JournalCommand::<OrderSpec>::Cancel(8))
+            .unwrap();
+
+        let mut bytes = writer.into_sink();
+        let last = bytes.len() - 6;
+        bytes[last] ^= 0xff;
+
+        let mut reader = JournalReader::<OrderSpec>::new(&bytes);
+        let record = reader.next().unwrap().unwrap();
+        assert_eq!(record.timestamp, 10);
+        assert!(matches!(record.command, JournalCommand::Cancel(7)));
+        assert!(matches!(
+            reader.next(),
+            Some(Err(JournalError::ChecksumMismatch { sequence: 2 }))
+        ));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn journal_corrupted_length_test() {
+        let mut journaled = journaled_book();
+        _ = journaled.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 101, 4));
+        _ = journaled.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 102, 2));
+        _ = journaled.insert_order(&OrderSpec::limit_price(3, OrderSide::Sell, 103, 1));
+        let (_, writer, _) = journaled.into_parts();
+        let bytes = writer.into_sink();
+
+        let first_len = u32::from_le_bytes(bytes[..4].try_into().unwrap()) as usize;
+        let second = first_len + 4;
+
+        // Length beyond any record
+        let mut corrupted = bytes.clone();
+        corrupted[second..second + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let mut book = OrderBook::<OrderSpec>::new(100);
+        assert!(matches!(
+            recover(&corrupted, &mut book),
+            Err(JournalError::InvalidRecordLength { position, .. }) if position == second
+        ));
+
+        // Length running past the end while the next record is still complete
+        let mut corrupted = bytes.clone();
+        let len = (bytes.len() - second) as u32;
+        corrupted[second..second + 4].copy_from_slice(&len.to_le_bytes());
+        let mut book = OrderBook::<OrderSpec>::new(100);
+        assert!(matches!(
+            recover(&corrupted, &mut book),
+            Err(JournalError::InvalidRecordLength { position, .. }) if position == second
+        ));
+
+        // Torn last record is still a clean end
+        let mut book = OrderBook::<OrderSpec>::new(100);
+        let recovery = recover(&bytes[..bytes.len() - 1], &mut book).unwrap();
+        assert_eq!(recovery.record_count, 2);
+    }
+
+    #[test]
+    fn journal_snapshot_recover_test() {
+        let mut journaled = journaled_book();
+        _ = journaled.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 101, 4));
+        _ = journaled.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 99, 2));
+        let snapshot = journaled.snapshot();
+
+        _ = journaled.insert_order(&OrderSpec::limit_price(3, OrderSide::Buy, 101, 1));
+        _ = journaled.cancel_order_by_id(2);
+        let (book, writer, _) = journaled.into_parts();
+
+        // Only the records after the snapshot are replayed
+        let mut recovered = OrderBook::<OrderSpec>::new(100);
+        let next_sequence = recovered.restore(&snapshot).unwrap();
+        assert_eq!(next_sequence, 3);
+        let recovery = recover_from(writer.sink(), &mut recovered, next_sequence).unwrap();
+        assert_eq!(recovery.record_count, 2);
+        assert_eq!(recovery.next_sequence, 5);
+        assert_eq!(book_state(&recovered), book_state(&book));
+
+        // Journal starting after the snapshot misses records
+        let mut later = JournalWriter::with_sequence(Vec::new(), 5);
+        later
+            .append(0, &JournalCommand::<OrderSpec>::Cancel(1))
+            .unwrap();
+        let mut recovered = OrderBook::<OrderSpec>::new(100);
+        assert!(matches!(
+            recover_from(later.sink(), &mut recovered, next_sequence),
+            Err(JournalError::SequenceGap {
+                expected: 3,
+                found: 5
+            })
+        ));
+
+        // Journal ending before the snapshot
+        assert!(matches!(
+            recover_from(writer.sink(), &mut recovered, 9),
+            Err(JournalError::SequenceGap {
+                expected: 9,
+                found: 5
+            })
+        ));
+    }
+
+    #[test]
+    fn journal_instrument_spec_test() {
+        let mut journaled = journaled_book();
+        let instrument_spec = InstrumentSpec {
+            tick_size: 5,
+            lot_size: 10,
+            ..Default::default()
+        };
+
+        _ = journaled.insert_order(&OrderSpec::limit_price(1, OrderSide::Buy, 101, 3));
+        assert!(journaled.set_instrument_spec(instrument_spec).is_ok());
+        _ = journaled.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 101, 3));
+        _ = journaled.insert_order(&OrderSpec::limit_price(3, OrderSide::Buy, 100, 10));
+
+        // Replay rejects the same orders under the same spec
+        let (book, writer, _) = journaled.into_parts();
+        let mut recovered = OrderBook::<OrderSpec>::new(10_000);
+        assert!(recover(writer.sink(), &mut recovered).is_ok());
+        assert_eq!(recovered.instrument_spec(), &instrument_spec);
+        assert!(recovered.get_order(1).is_some());
+        assert!(recovered.get_order(2).is_none());
+        assert_eq!(book_state(&recovered), book_state(&book));
+    }
+}
@@ -1,11 +1,12 @@
 #[cfg(test)]
 mod tests {
-    use std::time::Instant;
+    use std::time::Duration;
 
     use fake::{
         Rng,
         rand::{SeedableRng, rngs::StdRng},
     };
+    use market_forge::clock::{Clock, SystemClock};
     use market_forge::core::{
         order::{OrderId, OrderSide, Price, Quantity},
         order_book::OrderBook,
@@ -15,7 +16,8 @@ mod tests {
     #[test]
     fn latency_order_book_test() {
         let num_to_try = 10_000_000;
-        let mut book = OrderBook::<OrderSpec>::new(1_500_000);
+        let mut book =
+            OrderBook::<OrderSpec, (), SystemClock>::with_clock(1_500_000, SystemClock::new());
         let mut timestamps = Vec::with_capacity(num_to_try + 1);
 
         // Use a fixed seed for reproducibility
@@ -34,25 +36,24 @@ mod tests {
             };
             let order = OrderSpec::limit_price(i as OrderId, side, price, qty);
 
-            let now = Instant::now();
-            book.insert_order(&order);
-            timestamps.push(now);
+            _ = book.insert_order(&order);
+            timestamps.push(book.transact_time());
         }
         // Final timestamp
-        let end = Instant::now();
+        let end = book.clock().now();
         timestamps.push(end);
 
         // Print latency histogram
         println!("Latency (ns):");
         for w in timestamps.windows(2) {
-            let elapsed = w[1].duration_since(w[0]).as_nanos();
+            let elapsed = w[1] - w[0];
             println!("{}ns", elapsed);
         }
 
         // Calculate average latency
         let durations: Vec<_> = timestamps
             .windows(2)
-            .map(|w| w[1].duration_since(w[0]))
+            .map(|w| Duration::from_nanos(w[1] - w[0]))
             .collect();
         let total: std::time::Duration = durations.iter().sum();
         let avg = total / durations.len() as u32;
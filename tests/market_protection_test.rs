@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use market_forge::core::{
+        order::{MarketProtection, Order, OrderSide, TimeInForce},
+        order_book::OrderBook,
+        order_event::{CancelReason, OrderEvent},
+        order_spec::OrderSpec,
+    };
+
+    fn book_with_asks() -> OrderBook<OrderSpec> {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+        book.set_tick_size(5);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 100, 5));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 105, 5));
+        _ = book.insert_order(&OrderSpec::limit_price(3, OrderSide::Sell, 120, 5));
+
+        book
+    }
+
+    #[test]
+    fn market_protection_ticks_test() {
+        let mut book = book_with_asks();
+
+        // Two ticks above the best ask, the remaining quantity rests at 110
+        let res = book
+            .insert_order(
+                &OrderSpec::market(4, OrderSide::Buy, 12)
+                    .with_market_protection(MarketProtection::Ticks(2)),
+            )
+            .unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(
+            book.events().last(),
+            Some(&OrderEvent::Rested {
+                order_id: 4,
+                price: 110,
+                quantity: 2,
+            })
+        );
+        assert_eq!(book.asks().total_quantity(), 5);
+        assert_eq!(book.get_order(4).unwrap().price(), 110);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn market_protection_basis_points_test() {
+        let mut book = book_with_asks();
+
+        // 800 bps of 100 is 8, rounded down to the tick, immediate-or-cancel never rests
+        let res = book
+            .insert_order(
+                &OrderSpec::market(4, OrderSide::Buy, 12)
+                    .with_market_protection(MarketProtection::BasisPoints(800))
+                    .with_time_in_force(TimeInForce::IOC),
+            )
+            .unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(
+            book.events().last(),
+            Some(&OrderEvent::Cancelled {
+                order_id: 4,
+                quantity: 2,
+                reason: CancelReason::ImmediateOrCancel,
+            })
+        );
+        assert_eq!(book.bids().len(), 0);
+        assert_eq!(book.asks().total_quantity(), 5);
+    }
+
+    #[test]
+    fn market_protection_no_liquidity_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        // Without a best price there is no protection price to rest at
+        _ = book.insert_order(
+            &OrderSpec::market(1, OrderSide::Sell, 5)
+                .with_market_protection(MarketProtection::Ticks(2)),
+        );
+        assert_eq!(
+            book.events().last(),
+            Some(&OrderEvent::Cancelled {
+                order_id: 1,
+                quantity: 5,
+                reason: CancelReason::NoLiquidity,
+            })
+        );
+        assert_eq!(book.order_allocator().len(), 0);
+    }
+}
@@ -0,0 +1,268 @@
+#[cfg(test)]
+mod tests {
+    use market_forge::core::{
+        order::OrderSide,
+        order_book::OrderBook,
+        order_error::OrderError,
+        order_event::{CancelReason, OrderEvent},
+        order_link::OcoMode,
+        order_spec::OrderSpec,
+    };
+
+    // Events of the linked leg, without the order flow of the last operation
+    fn linked_events(book: &OrderBook<OrderSpec>) -> Vec<OrderEvent> {
+        book.events()
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    OrderEvent::Cancelled { .. } | OrderEvent::Reduced { .. }
+                )
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn book_with_market_price() -> OrderBook<OrderSpec> {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Buy, 100, 10));
+        _ = book.insert_order(&OrderSpec::market(2, OrderSide::Sell, 1));
+        assert_eq!(book.current_market_price(), 100);
+
+        book
+    }
+
+    #[test]
+    fn oco_take_profit_test() {
+        let mut book = book_with_market_price();
+
+        // Take profit at 110, stop loss at 95
+        let res = book.insert_oco_order(
+            &OrderSpec::limit_price(3, OrderSide::Sell, 110, 5),
+            &OrderSpec::stop_market(4, OrderSide::Sell, 95, 5),
+            OcoMode::Cancel,
+        );
+        assert!(res.unwrap().is_empty());
+        assert_eq!(book.asks().total_quantity(), 5);
+        assert_eq!(book.stop_asks().total_quantity(), 5);
+
+        // Any fill on take profit cancel the stop loss
+        let res = book
+            .insert_order(&OrderSpec::limit_price(5, OrderSide::Buy, 110, 2))
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(
+            linked_events(&book),
+            vec![OrderEvent::Cancelled {
+                order_id: 4,
+                quantity: 5,
+                reason: CancelReason::OneCancelsOther,
+            }]
+        );
+        assert_eq!(book.stop_asks().len(), 0);
+        assert_eq!(book.asks().total_quantity(), 3);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn oco_stop_loss_test() {
+        let mut book = book_with_market_price();
+
+        _ = book.insert_oco_order(
+            &OrderSpec::limit_price(3, OrderSide::Sell, 110, 5),
+            &OrderSpec::stop_market(4, OrderSide::Sell, 95, 5),
+            OcoMode::Cancel,
+        );
+
+        // Trade at 95 trigger the stop loss and cancel the take profit
+        _ = book.insert_order(&OrderSpec::limit_price(5, OrderSide::Buy, 95, 10));
+        let res = book
+            .insert_order(&OrderSpec::market(6, OrderSide::Sell, 10))
+            .unwrap();
+        let matches: Vec<_> = res.iter().map(|m| (m.match_from_id, m.quantity)).collect();
+        assert_eq!(matches, vec![(6, 9), (6, 1), (4, 5)]);
+
+        assert_eq!(
+            linked_events(&book),
+            vec![OrderEvent::Cancelled {
+                order_id: 3,
+                quantity: 5,
+                reason: CancelReason::OneCancelsOther,
+            }]
+        );
+        assert_eq!(book.asks().len(), 0);
+        assert_eq!(book.order_allocator().len(), 1);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn oco_reduce_test() {
+        let mut book = book_with_market_price();
+
+        _ = book.insert_oco_order(
+            &OrderSpec::limit_price(3, OrderSide::Sell, 110, 5),
+            &OrderSpec::stop_market(4, OrderSide::Sell, 95, 5),
+            OcoMode::Reduce,
+        );
+
+        // Partial fill reduce the other leg
+        _ = book.insert_order(&OrderSpec::limit_price(5, OrderSide::Buy, 110, 2));
+        assert_eq!(
+            linked_events(&book),
+            vec![OrderEvent::Reduced {
+                order_id: 4,
+                quantity: 3,
+            }]
+        );
+        assert_eq!(book.stop_asks().total_quantity(), 3);
+
+        // Remaining fill cancel the other leg
+        _ = book.insert_order(&OrderSpec::limit_price(6, OrderSide::Buy, 110, 5));
+        assert_eq!(
+            linked_events(&book),
+            vec![OrderEvent::Cancelled {
+                order_id: 4,
+                quantity: 3,
+                reason: CancelReason::OneCancelsOther,
+            }]
+        );
+        assert_eq!(book.stop_asks().len(), 0);
+        assert_eq!(book.bids().total_quantity(), 11);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn oco_filled_on_arrival_test() {
+        let mut book = book_with_market_price();
+
+        // First leg is filled right away, second leg never reach the book
+        let res = book
+            .insert_oco_order(
+                &OrderSpec::limit_price(3, OrderSide::Sell, 100, 5),
+                &OrderSpec::stop_market(4, OrderSide::Sell, 95, 5),
+                OcoMode::Cancel,
+            )
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert!(linked_events(&book).is_empty());
+        assert_eq!(
+            book.events().last(),
+            Some(&OrderEvent::Rejected {
+                order_id: 4,
+                reason: OrderError::LinkedOrderFilled,
+            })
+        );
+        assert_eq!(book.stop_asks().len(), 0);
+
+        // Neither leg is left linked, the unplaced id can be used again
+        let res = book.insert_oco_order(
+            &OrderSpec::limit_price(4, OrderSide::Sell, 110, 5),
+            &OrderSpec::stop_market(5, OrderSide::Sell, 95, 5),
+            OcoMode::Cancel,
+        );
+        assert!(res.unwrap().is_empty());
+        assert_eq!(book.stop_asks().total_quantity(), 5);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn oco_already_linked_test() {
+        let mut book = book_with_market_price();
+
+        // Order can not be linked to itself
+        let res = book.insert_oco_order(
+            &OrderSpec::limit_price(3, OrderSide::Sell, 110, 5),
+            &OrderSpec::stop_market(3, OrderSide::Sell, 95, 5),
+            OcoMode::Cancel,
+        );
+        assert_eq!(res, Err(OrderError::OrderAlreadyLinked));
+
+        _ = book.insert_oco_order(
+            &OrderSpec::limit_price(3, OrderSide::Sell, 110, 5),
+            &OrderSpec::stop_market(4, OrderSide::Sell, 95, 5),
+            OcoMode::Cancel,
+        );
+
+        // Order can only be part of one pair
+        let res = book.insert_oco_order(
+            &OrderSpec::limit_price(5, OrderSide::Sell, 120, 5),
+            &OrderSpec::stop_market(4, OrderSide::Sell, 90, 5),
+            OcoMode::Cancel,
+        );
+        assert_eq!(res, Err(OrderError::OrderAlreadyLinked));
+        assert_eq!(book.asks().total_quantity(), 5);
+
+        // Cancelling one leg keep the other leg in the book
+        _ = book.cancel_order(&OrderSpec::cancel(3, OrderSide::Sell, 110));
+        assert_eq!(book.stop_asks().total_quantity(), 5);
+        assert_eq!(
+            book.events(),
+            &[OrderEvent::Cancelled {
+                order_id: 3,
+                quantity: 5,
+                reason: CancelReason::Requested,
+            }]
+        );
+
+        let res = book.insert_oco_order(
+            &OrderSpec::limit_price(5, OrderSide::Sell, 120, 5),
+            &OrderSpec::limit_price(6, OrderSide::Sell, 130, 5),
+            OcoMode::Cancel,
+        );
+        assert!(res.is_ok());
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn oco_triggered_without_liquidity_test() {
+        let mut book = book_with_market_price();
+
+        _ = book.insert_oco_order(
+            &OrderSpec::limit_price(10, OrderSide::Sell, 110, 5),
+            &OrderSpec::stop_market(11, OrderSide::Sell, 95, 5),
+            OcoMode::Cancel,
+        );
+        _ = book.cancel_order_by_id(1);
+
+        // Trade at 95 triggers the stop loss, nothing left to sell to
+        _ = book.insert_order(&OrderSpec::limit_price(5, OrderSide::Buy, 95, 1));
+        _ = book.insert_order(&OrderSpec::limit_price(6, OrderSide::Sell, 95, 1));
+        assert!(book.events().contains(&OrderEvent::Cancelled {
+            order_id: 11,
+            quantity: 5,
+            reason: CancelReason::NoLiquidity,
+        }));
+        assert_eq!(book.stop_asks().len(), 0);
+        assert_eq!(book.asks().total_quantity(), 5);
+
+        // Neither id is linked anymore
+        let res = book.insert_oco_order(
+            &OrderSpec::limit_price(11, OrderSide::Sell, 111, 5),
+            &OrderSpec::stop_market(12, OrderSide::Sell, 90, 5),
+            OcoMode::Cancel,
+        );
+        assert!(res.unwrap().is_empty());
+        assert!(book.cancel_order_by_id(10).is_ok());
+        assert_eq!(book.stop_asks().total_quantity(), 5);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+}
@@ -0,0 +1,258 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use fake::{
+        Rng,
+        rand::{SeedableRng, rngs::StdRng},
+    };
+    use market_forge::core::{
+        order::{Order, OrderId, OrderSide, Price, Quantity, TimeInForce},
+        order_book::OrderBook,
+        order_depth::{DepthChange, DepthChangeKind, PriceLevel},
+        order_spec::OrderSpec,
+    };
+
+    fn level(price: Price, quantity: Quantity, order_count: usize) -> PriceLevel {
+        PriceLevel {
+            price,
+            quantity,
+            order_count,
+        }
+    }
+
+    #[test]
+    fn depth_aggregation_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Buy, 99, 2));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 99, 3));
+        _ = book.insert_order(&OrderSpec::limit_price(3, OrderSide::Buy, 98, 1));
+        _ = book.insert_order(&OrderSpec::limit_price(4, OrderSide::Buy, 97, 1));
+        _ = book.insert_order(&OrderSpec::limit_price(5, OrderSide::Sell, 101, 4));
+        _ = book.insert_order(&OrderSpec::limit_price(6, OrderSide::Sell, 103, 6));
+
+        let depth = book.depth(2);
+        assert_eq!(depth.bids, vec![level(99, 5, 2), level(98, 1, 1)]);
+        assert_eq!(depth.asks, vec![level(101, 4, 1), level(103, 6, 1)]);
+
+        // Cancelled order no longer counts
+        _ = book.cancel_order_by_id(1);
+        assert_eq!(book.depth(1).bids, vec![level(99, 3, 1)]);
+        assert!(book.depth(0).asks.is_empty());
+    }
+
+    #[test]
+    fn depth_cache_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+        assert!(book.depth_cache().is_none());
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 101, 4));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 102, 2));
+        _ = book.insert_order(&OrderSpec::limit_price(3, OrderSide::Sell, 103, 6));
+        book.set_depth_cache(2);
+        assert_eq!(
+            book.depth_cache().unwrap().asks,
+            vec![level(101, 4, 1), level(102, 2, 1)]
+        );
+
+        // Level behind the cache moves in once the best level is taken
+        _ = book.insert_order(&OrderSpec::limit_price(4, OrderSide::Buy, 101, 4));
+        assert_eq!(
+            book.depth_cache().unwrap().asks,
+            vec![level(102, 2, 1), level(103, 6, 1)]
+        );
+
+        // Better level pushes the worst one out
+        _ = book.insert_order(&OrderSpec::limit_price(5, OrderSide::Sell, 100, 1));
+        assert_eq!(
+            book.depth_cache().unwrap().asks,
+            vec![level(100, 1, 1), level(102, 2, 1)]
+        );
+
+        book.set_depth_cache(0);
+        assert!(book.depth_cache().is_none());
+    }
+
+    #[test]
+    fn depth_cache_random_test() {
+        let mut book = OrderBook::<OrderSpec>::new(10_000);
+        let mut rng = StdRng::seed_from_u64(12);
+        book.set_depth_cache(5);
+
+        for i in 0..10_000 {
+            let order_id = i as OrderId;
+            let order_side = if rng.random_bool(0.5) {
+                OrderSide::Buy
+            } else {
+                OrderSide::Sell
+            };
+            let price = rng.random_range(90..110) as Price;
+            let quantity = rng.random_range(1..10) as Quantity;
+
+            match rng.random_range(0..10) {
+                0 => _ = book.insert_order(&OrderSpec::market(order_id, order_side, quantity)),
+                1 => {
+                    _ = book.insert_order(
+                        &OrderSpec::limit_price(order_id, order_side, price, quantity)
+                            .with_time_in_force(TimeInForce::IOC),
+                    )
+                }
+                2 | 3 => _ = book.cancel_order_by_id(rng.random_range(0..order_id + 1)),
+                4 => _ = book.replace_order_by_id(rng.random_range(0..order_id + 1), -1, 0),
+                _ => {
+                    _ = book.insert_order(&OrderSpec::limit_price(
+                        order_id, order_side, price, quantity,
+                    ))
+                }
+            }
+
+            if let Some(err) = book.validate_cache().err() {
+                panic!("{}: {:?}", i, err);
+            }
+        }
+    }
+
+    #[test]
+    fn depth_feed_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+        book.set_depth_feed(true);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 101, 4));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 101, 2));
+        assert_eq!(
+            book.depth_changes(),
+            &[DepthChange {
+                sequence: 2,
+                order_side: OrderSide::Sell,
+                price: 101,
+                quantity: 6,
+                order_count: 2,
+                kind: DepthChangeKind::Changed,
+            }]
+        );
+
+        // Level is removed, then the remaining quantity adds a level at bids
+        _ = book.insert_order(&OrderSpec::limit_price(3, OrderSide::Buy, 102, 7));
+        let changes: Vec<(u64, OrderSide, Price, Quantity, DepthChangeKind)> = book
+            .depth_changes()
+            .iter()
+            .map(|c| (c.sequence, c.order_side, c.price, c.quantity, c.kind))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (3, OrderSide::Sell, 101, 0, DepthChangeKind::Removed),
+                (4, OrderSide::Buy, 102, 1, DepthChangeKind::Added),
+            ]
+        );
+
+        // Rejected operation doesn't touch the book
+        _ = book.cancel_order_by_id(1);
+        assert!(book.depth_changes().is_empty());
+
+        book.set_depth_feed(false);
+        _ = book.cancel_order_by_id(3);
+        assert!(book.depth_changes().is_empty());
+    }
+
+    #[test]
+    fn depth_feed_mirror_test() {
+        let mut book = OrderBook::<OrderSpec>::new(10_000);
+        let mut rng = StdRng::seed_from_u64(13);
+        book.set_depth_feed(true);
+
+        // Subscriber side, rebuilt from the changes only
+        let mut mirror: BTreeMap<(bool, Price), (Quantity, usize)> = BTreeMap::new();
+        let mut sequence = 0;
+
+        for i in 0..10_000 {
+            let order_id = i as OrderId;
+            let order_side = if rng.random_bool(0.5) {
+                OrderSide::Buy
+            } else {
+                OrderSide::Sell
+            };
+            let price = rng.random_range(90..110) as Price;
+            let quantity = rng.random_range(1..10) as Quantity;
+
+            match rng.random_range(0..6) {
+                0 | 1 => _ = book.cancel_order_by_id(rng.random_range(0..order_id + 1)),
+                2 => _ = book.replace_order_by_id(rng.random_range(0..order_id + 1), -1, 1),
+                _ => {
+                    _ = book.insert_order(&OrderSpec::limit_price(
+                        order_id, order_side, price, quantity,
+                    ))
+                }
+            }
+
+            for change in book.depth_changes() {
+                sequence += 1;
+                assert_eq!(change.sequence, sequence);
+
+                let key = (change.order_side.is_buy(), change.price);
+                match change.kind {
+                    DepthChangeKind::Added => {
+                        assert!(
+                            mirror
+                                .insert(key, (change.quantity, change.order_count))
+                                .is_none()
+                        )
+                    }
+                    DepthChangeKind::Changed => {
+                        assert!(
+                            mirror
+                                .insert(key, (change.quantity, change.order_count))
+                                .is_some()
+                        )
+                    }
+                    DepthChangeKind::Removed => assert!(mirror.remove(&key).is_some()),
+                }
+            }
+
+            let depth = book.depth(usize::MAX);
+            let levels: Vec<PriceLevel> = mirror
+                .iter()
+                .rev()
+                .filter(|((is_buy, _), _)| *is_buy)
+                .chain(mirror.iter().filter(|((is_buy, _), _)| !*is_buy))
+                .map(|(&(_, price), &(quantity, order_count))| level(price, quantity, order_count))
+                .collect();
+            assert_eq!(levels, [depth.bids, depth.asks].concat());
+        }
+    }
+
+    #[test]
+    fn book_orders_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Buy, 99, 2));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 100, 3));
+        _ = book.insert_order(&OrderSpec::limit_price(3, OrderSide::Buy, 99, 1));
+        _ = book.insert_order(&OrderSpec::limit_price(4, OrderSide::Buy, 99, 5));
+        _ = book.insert_order(&OrderSpec::limit_price(5, OrderSide::Sell, 102, 4));
+        _ = book.insert_order(&OrderSpec::limit_price(6, OrderSide::Sell, 101, 6));
+
+        // Cancelled order leaves no gap in the queue
+        _ = book.cancel_order_by_id(3);
+
+        let bids: Vec<(Price, usize, OrderId, Quantity)> = book
+            .bid_orders()
+            .map(|item| {
+                (
+                    item.price,
+                    item.queue_position,
+                    item.order.id(),
+                    item.order.quantity(),
+                )
+            })
+            .collect();
+        assert_eq!(bids, vec![(100, 0, 2, 3), (99, 0, 1, 2), (99, 1, 4, 5)]);
+
+        let asks: Vec<(Price, usize, OrderId)> = book
+            .ask_orders()
+            .map(|item| (item.price, item.queue_position, item.order.id()))
+            .collect();
+        assert_eq!(asks, vec![(101, 0, 6), (102, 0, 5)]);
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests {
+    use market_forge::core::{
+        order::{Order, OrderSide, PostOnly, TimeInForce},
+        order_book::OrderBook,
+        order_error::OrderError,
+        order_event::{CancelReason, LiquidityRole, OrderEvent},
+        order_spec::OrderSpec,
+    };
+
+    #[test]
+    fn event_partial_fill_rest_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 100, 3));
+        assert_eq!(
+            book.events(),
+            &[
+                OrderEvent::Accepted { order_id: 1 },
+                OrderEvent::Rested {
+                    order_id: 1,
+                    price: 100,
+                    quantity: 3,
+                },
+            ]
+        );
+
+        // Partially filled, then rest the remaining quantity
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 101, 5));
+        assert_eq!(
+            book.events(),
+            &[
+                OrderEvent::Accepted { order_id: 2 },
+                OrderEvent::Fill {
+                    order_id: 2,
+                    contra_order_id: 1,
+                    price: 100,
+                    quantity: 3,
+                    leaves_quantity: 2,
+                    role: LiquidityRole::Taker,
+                },
+                OrderEvent::Fill {
+                    order_id: 1,
+                    contra_order_id: 2,
+                    price: 100,
+                    quantity: 3,
+                    leaves_quantity: 0,
+                    role: LiquidityRole::Maker,
+                },
+                OrderEvent::Rested {
+                    order_id: 2,
+                    price: 101,
+                    quantity: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn event_immediate_order_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 100, 3));
+
+        // Fill-or-kill is killed without any fill
+        _ = book.insert_order(
+            &OrderSpec::limit_price(2, OrderSide::Buy, 100, 5).with_time_in_force(TimeInForce::FOK),
+        );
+        assert_eq!(
+            book.events(),
+            &[
+                OrderEvent::Accepted { order_id: 2 },
+                OrderEvent::Cancelled {
+                    order_id: 2,
+                    quantity: 5,
+                    reason: CancelReason::FillOrKill,
+                },
+            ]
+        );
+
+        // Immediate-or-cancel is partially filled, then cancelled
+        _ = book.insert_order(
+            &OrderSpec::limit_price(3, OrderSide::Buy, 100, 5).with_time_in_force(TimeInForce::IOC),
+        );
+        assert_eq!(book.events().len(), 4);
+        assert_eq!(
+            book.events().last(),
+            Some(&OrderEvent::Cancelled {
+                order_id: 3,
+                quantity: 2,
+                reason: CancelReason::ImmediateOrCancel,
+            })
+        );
+
+        assert_eq!(book.order_allocator().len(), 0);
+    }
+
+    #[test]
+    fn event_rejected_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 100, 3));
+
+        let res = book.insert_order(
+            &OrderSpec::limit_price(2, OrderSide::Buy, 100, 5).with_post_only(PostOnly::Reject),
+        );
+        assert!(res.is_err());
+        assert_eq!(
+            book.events(),
+            &[OrderEvent::Rejected {
+                order_id: 2,
+                reason: OrderError::PostOnlyWouldCross,
+            }]
+        );
+
+        let res = book.cancel_order_by_id(2);
+        assert!(res.is_err());
+        assert_eq!(
+            book.events(),
+            &[OrderEvent::Rejected {
+                order_id: 2,
+                reason: OrderError::OrderNotFound,
+            }]
+        );
+    }
+
+    #[test]
+    fn event_replace_cancel_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 100, 3));
+
+        _ = book.replace_order_by_id(1, -1, 0);
+        assert_eq!(
+            book.events(),
+            &[OrderEvent::Replaced {
+                order_id: 1,
+                price: 100,
+                quantity: 2,
+            }]
+        );
+
+        _ = book.replace_order_by_id(1, 0, 101);
+        assert_eq!(
+            book.events(),
+            &[
+                OrderEvent::Replaced {
+                    order_id: 1,
+                    price: 101,
+                    quantity: 2,
+                },
+                OrderEvent::Rested {
+                    order_id: 1,
+                    price: 101,
+                    quantity: 2,
+                },
+            ]
+        );
+
+        _ = book.cancel_order_by_id(1);
+        assert_eq!(
+            book.events(),
+            &[OrderEvent::Cancelled {
+                order_id: 1,
+                quantity: 2,
+                reason: CancelReason::Requested,
+            }]
+        );
+    }
+}
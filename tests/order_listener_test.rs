@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod tests {
+    use market_forge::core::{
+        order::{OrderId, OrderSide, Price, Quantity},
+        order_book::OrderBook,
+        order_error::OrderError,
+        order_event::{CancelReason, LiquidityRole},
+        order_listener::OrderListener,
+        order_spec::OrderSpec,
+    };
+
+    #[derive(Debug, PartialEq)]
+    enum Callback {
+        Accept(OrderId),
+        Rest(OrderId, Quantity),
+        Fill(OrderId, Quantity, LiquidityRole),
+        Cancel(OrderId, CancelReason),
+        Reject(OrderId, OrderError),
+        Replace(OrderId, Price, Quantity),
+        BookChange(OrderSide, Price, Quantity),
+    }
+
+    #[derive(Default)]
+    struct RecordingListener {
+        callbacks: Vec<Callback>,
+    }
+
+    impl OrderListener for RecordingListener {
+        fn on_accept(&mut self, order_id: OrderId) {
+            self.callbacks.push(Callback::Accept(order_id));
+        }
+
+        fn on_rest(&mut self, order_id: OrderId, _price: Price, quantity: Quantity) {
+            self.callbacks.push(Callback::Rest(order_id, quantity));
+        }
+
+        fn on_fill(
+            &mut self,
+            order_id: OrderId,
+            _contra_order_id: OrderId,
+            _price: Price,
+            quantity: Quantity,
+            _leaves_quantity: Quantity,
+            role: LiquidityRole,
+        ) {
+            self.callbacks
+                .push(Callback::Fill(order_id, quantity, role));
+        }
+
+        fn on_cancel(&mut self, order_id: OrderId, _quantity: Quantity, reason: CancelReason) {
+            self.callbacks.push(Callback::Cancel(order_id, reason));
+        }
+
+        fn on_reject(&mut self, order_id: OrderId, reason: &OrderError) {
+            self.callbacks
+                .push(Callback::Reject(order_id, reason.clone()));
+        }
+
+        fn on_replace(&mut self, order_id: OrderId, price: Price, quantity: Quantity) {
+            self.callbacks
+                .push(Callback::Replace(order_id, price, quantity));
+        }
+
+        fn on_book_change(&mut self, order_side: OrderSide, price: Price, quantity: Quantity) {
+            self.callbacks
+                .push(Callback::BookChange(order_side, price, quantity));
+        }
+    }
+
+    fn drain(book: &mut OrderBook<OrderSpec, RecordingListener>) -> Vec<Callback> {
+        std::mem::take(&mut book.listener_mut().callbacks)
+    }
+
+    #[test]
+    fn listener_match_test() {
+        let mut book = OrderBook::with_listener(100, RecordingListener::default());
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 100, 3));
+        assert_eq!(
+            drain(&mut book),
+            vec![
+                Callback::Accept(1),
+                Callback::Rest(1, 3),
+                Callback::BookChange(OrderSide::Sell, 100, 3),
+            ]
+        );
+
+        // Level is gone once the maker is filled, remaining quantity rests at bids
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 101, 5));
+        assert_eq!(
+            drain(&mut book),
+            vec![
+                Callback::Accept(2),
+                Callback::Fill(2, 3, LiquidityRole::Taker),
+                Callback::Fill(1, 3, LiquidityRole::Maker),
+                Callback::BookChange(OrderSide::Sell, 100, 0),
+                Callback::Rest(2, 2),
+                Callback::BookChange(OrderSide::Buy, 101, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn listener_replace_cancel_reject_test() {
+        let mut book = OrderBook::with_listener(100, RecordingListener::default());
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Buy, 100, 3));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 100, 4));
+        drain(&mut book);
+
+        _ = book.replace_order_by_id(1, -1, 0);
+        assert_eq!(
+            drain(&mut book),
+            vec![
+                Callback::Replace(1, 100, 2),
+                Callback::BookChange(OrderSide::Buy, 100, 6),
+            ]
+        );
+
+        _ = book.cancel_order_by_id(2);
+        assert_eq!(
+            drain(&mut book),
+            vec![
+                Callback::Cancel(2, CancelReason::Requested),
+                Callback::BookChange(OrderSide::Buy, 100, 2),
+            ]
+        );
+
+        _ = book.cancel_order_by_id(2);
+        assert_eq!(
+            drain(&mut book),
+            vec![Callback::Reject(2, OrderError::OrderNotFound)]
+        );
+
+        // Events stay available beside the listener
+        assert_eq!(book.events().len(), 1);
+    }
+}
@@ -3,10 +3,12 @@ mod tests {
     use std::mem;
 
     use market_forge::core::{
-        order::{Order, OrderSide, TimeInForce},
+        order::{Order, OrderSide, PostOnly, TimeInForce},
         order_book::OrderBook,
         order_error::OrderError,
+        order_event::{CancelReason, OrderEvent},
         order_match::OrderMatch,
+        order_replace::ReplaceOutcome,
         order_spec::OrderSpec,
     };
 
@@ -122,7 +124,7 @@ mod tests {
         );
         // Should match None
         assert!(
-            res.is_none(),
+            res.unwrap().is_empty(),
             "should not return any match order because full or cancel"
         );
 
@@ -136,9 +138,10 @@ mod tests {
         // Should match None
         assert_eq!(
             res,
-            Some(vec![OrderMatch {
+            Ok(vec![OrderMatch {
                 match_from_id: 4,
                 match_to_id: 1,
+                timestamp: 0,
                 order_side: OrderSide::Buy,
                 price: 119,
                 quantity: 12,
@@ -224,7 +227,7 @@ mod tests {
 
         {
             let match_order = book.insert_order(&OrderSpec::market(7, OrderSide::Buy, 10));
-            assert!(match_order.is_none(), "no match order found");
+            assert!(match_order.unwrap().is_empty(), "no match order found");
         }
 
         println!("{}", book);
@@ -234,6 +237,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn order_market_remainder_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 120, 10));
+
+        // Remaining quantity is cancelled instead of resting at price 0
+        let match_order = book
+            .insert_order(&OrderSpec::market(2, OrderSide::Buy, 15))
+            .unwrap();
+        assert_eq!(match_order.len(), 1);
+        assert_eq!(
+            book.events().last(),
+            Some(&OrderEvent::Cancelled {
+                order_id: 2,
+                quantity: 5,
+                reason: CancelReason::NoLiquidity,
+            })
+        );
+        assert_eq!(book.bids().len(), 0);
+        assert_eq!(book.order_allocator().len(), 0);
+
+        // Triggered stop market order doesn't rest either
+        _ = book.insert_order(&OrderSpec::limit_price(3, OrderSide::Buy, 110, 2));
+        _ = book.insert_order(&OrderSpec::stop_market(4, OrderSide::Sell, 115, 5));
+        _ = book.insert_order(&OrderSpec::limit_price(5, OrderSide::Sell, 110, 1));
+        assert_eq!(book.asks().len(), 0);
+        assert_eq!(book.bids().len(), 0);
+        assert_eq!(book.order_allocator().len(), 0);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
     #[test]
     fn order_cancel_test() {
         let mut book = OrderBook::<OrderSpec>::new(100);
@@ -299,4 +337,221 @@ mod tests {
             panic!("{:?}", err);
         }
     }
+
+    #[test]
+    fn order_replace_priority_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 120, 5));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 120, 5));
+        _ = book.insert_order(&OrderSpec::limit_price(3, OrderSide::Sell, 120, 5));
+
+        // Quantity decrease keeps the queue position
+        let res = book.replace_order(&OrderSpec::replace(1, OrderSide::Sell, 120), -2, 0);
+        assert_eq!(res, Ok(ReplaceOutcome::InPlace { quantity: 3 }));
+        assert_eq!(book.asks().total_quantity(), 13);
+
+        // Quantity increase moves the order to the back of the queue
+        let res = book.replace_order(&OrderSpec::replace(2, OrderSide::Sell, 120), 1, 0);
+        assert_eq!(res, Ok(ReplaceOutcome::Requeued { matches: vec![] }));
+        assert_eq!(book.asks().total_quantity(), 14);
+
+        let ids: Vec<_> = book
+            .asks()
+            .get_orders(&120)
+            .unwrap()
+            .iter()
+            .map(|item| item.order_id())
+            .collect();
+        assert_eq!(ids, vec![1, 3, 2]);
+
+        // Delta can't take more than the order quantity
+        let res = book.replace_order(&OrderSpec::replace(3, OrderSide::Sell, 120), -6, 0);
+        assert_eq!(res, Err(OrderError::InvalidQuantityDelta));
+        assert_eq!(book.asks().total_quantity(), 14);
+
+        // Price change moves the order and match at the new price
+        _ = book.insert_order(&OrderSpec::limit_price(4, OrderSide::Buy, 118, 4));
+        let res = book.replace_order(&OrderSpec::replace(3, OrderSide::Sell, 120), 0, 118);
+        assert_eq!(
+            res,
+            Ok(ReplaceOutcome::Requeued {
+                matches: vec![OrderMatch {
+                    order_side: OrderSide::Sell,
+                    price: 118,
+                    quantity: 4,
+                    match_from_id: 3,
+                    match_to_id: 4,
+                    timestamp: 0,
+                }]
+            })
+        );
+        assert_eq!(book.asks().peek_key(), Some(&118));
+        assert_eq!(book.asks().total_quantity(), 10);
+        assert_eq!(book.bids().len(), 0);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn order_lookup_by_id_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 120, 5));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 120, 5));
+        _ = book.insert_order(&OrderSpec::limit_price(3, OrderSide::Sell, 120, 5));
+        _ = book.insert_order(&OrderSpec::stop_market(4, OrderSide::Sell, 100, 5));
+
+        assert_eq!(book.get_order(2).map(|o| o.quantity), Some(5));
+        assert_eq!(book.recover_order_price(OrderSide::Sell, 3), Some(120));
+        assert_eq!(book.recover_order_price(OrderSide::Buy, 3), None);
+
+        // Resting order id can't be reused
+        let res = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 100, 5));
+        assert_eq!(res, Err(OrderError::DuplicateOrderId));
+
+        // Cancel from the middle of the queue and the stop orders
+        assert_eq!(book.cancel_order_by_id(2).map(|o| o.id), Ok(2));
+        assert_eq!(book.cancel_order_by_id(4).map(|o| o.id), Ok(4));
+        assert_eq!(
+            book.cancel_order_by_id(2).err(),
+            Some(OrderError::OrderNotFound)
+        );
+        assert!(book.get_order(2).is_none());
+        assert_eq!(book.stop_asks().len(), 0);
+
+        // Cancelled order is skipped by the matching
+        let res = book
+            .insert_order(&OrderSpec::market(5, OrderSide::Buy, 8))
+            .unwrap();
+        let matches: Vec<_> = res.iter().map(|m| (m.match_to_id, m.quantity)).collect();
+        assert_eq!(matches, vec![(1, 5), (3, 3)]);
+
+        let res = book.replace_order_by_id(3, -1, 0);
+        assert_eq!(res, Ok(ReplaceOutcome::InPlace { quantity: 1 }));
+        assert_eq!(book.asks().total_quantity(), 1);
+        assert_eq!(book.order_allocator().len(), 1);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn order_last_trade_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+        assert_eq!(book.current_market_price(), 0, "no trade yet");
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 121, 12));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 120, 8));
+        assert_eq!(
+            book.current_market_price(),
+            0,
+            "resting order is not a trade"
+        );
+
+        _ = book.insert_order(&OrderSpec::limit_price(3, OrderSide::Buy, 121, 10));
+        assert_eq!(book.current_market_price(), 121, "last trade price");
+        assert_eq!(book.last_trade_quantity(), 2, "last trade quantity");
+
+        _ = book.insert_order(&OrderSpec::market(4, OrderSide::Buy, 1));
+        assert_eq!(book.current_market_price(), 121);
+        assert_eq!(book.last_trade_quantity(), 1);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn order_post_only_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 120, 8));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 118, 5));
+
+        // Crossing the spread
+        let res = book.insert_order(
+            &OrderSpec::limit_price(3, OrderSide::Buy, 120, 2).with_post_only(PostOnly::Reject),
+        );
+        assert_eq!(res, Err(OrderError::PostOnlyWouldCross));
+        assert_eq!(book.asks().total_quantity(), 8, "should not take liquidity");
+        assert_eq!(book.bids().total_quantity(), 5, "should not rest");
+
+        // Not crossing the spread
+        let res = book.insert_order(
+            &OrderSpec::limit_price(4, OrderSide::Sell, 119, 2).with_post_only(PostOnly::Reject),
+        );
+        assert!(res.unwrap().is_empty());
+        assert_eq!(book.asks().peek_key(), Some(&119));
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn order_post_only_slide_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+        book.set_tick_size(5);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 120, 8));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 110, 5));
+
+        // Slide one tick below the best ask
+        let res = book.insert_order(
+            &OrderSpec::limit_price(3, OrderSide::Buy, 125, 2).with_post_only(PostOnly::Slide),
+        );
+        assert!(res.unwrap().is_empty());
+        assert_eq!(book.bids().peek_key().map(|p| p.0), Some(115));
+        assert_eq!(book.asks().total_quantity(), 8);
+
+        // Slide one tick above the best bid
+        let res = book.insert_order(
+            &OrderSpec::limit_price(4, OrderSide::Sell, 100, 2).with_post_only(PostOnly::Slide),
+        );
+        assert!(res.unwrap().is_empty());
+        assert_eq!(book.asks().peek_key(), Some(&120));
+        assert_eq!(book.asks().total_quantity(), 10);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn order_queue_compaction_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        for id in 1..=10 {
+            _ = book.insert_order(&OrderSpec::limit_price(id, OrderSide::Sell, 100, 1));
+        }
+
+        // Cancelled orders in the middle leave empty slots until they're the most
+        for id in 2..=6 {
+            assert!(book.cancel_order_by_id(id).is_ok());
+        }
+        assert_eq!(book.asks().get_orders(&100).unwrap().empty_len(), 5);
+
+        assert!(book.cancel_order_by_id(7).is_ok());
+        let orders = book.asks().get_orders(&100).unwrap();
+        assert_eq!(orders.empty_len(), 0);
+        assert_eq!(orders.len(), 4);
+
+        // Orders are still found at their position and keep their priority
+        assert!(book.cancel_order_by_id(9).is_ok());
+        let res = book
+            .insert_order(&OrderSpec::limit_price(11, OrderSide::Buy, 100, 2))
+            .unwrap();
+        let matches: Vec<_> = res.iter().map(|m| m.match_to_id).collect();
+        assert_eq!(matches, vec![1, 8]);
+        assert!(book.replace_order_by_id(10, 0, 101).is_ok());
+        assert_eq!(book.asks().peek_key(), Some(&101));
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
 }
@@ -74,7 +74,7 @@ fn run_perf_test(duration_secs: u64, num_to_try: usize) -> bool {
             break;
         }
 
-        _ = book.cancel_order(&OrderSpec::cancel(order.id, order.order_side, order.price));
+        _ = book.cancel_order_by_id(order.id);
         order_count += 1;
     }
 
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use market_forge::{
+        clock::ManualClock,
+        core::order_book::OrderBook,
+        journal::{JournalWriter, JournaledBook},
+        replay::{
+            ReplayError, ReplayStep, diff_lines, format_step, journal_steps, parse_steps, replay,
+        },
+    };
+
+    fn golden_dir() -> &'static Path {
+        Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden"))
+    }
+
+    // Regenerate with `cargo run --bin replay -- <file>.cmd --golden <file>.golden --bless`
+    #[test]
+    fn replay_golden_test() {
+        let mut command_files: Vec<_> = fs::read_dir(golden_dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "cmd"))
+            .collect();
+        command_files.sort();
+        assert!(!command_files.is_empty());
+
+        for command_file in command_files {
+            let steps = parse_steps(&fs::read_to_string(&command_file).unwrap()).unwrap();
+            let golden = fs::read_to_string(command_file.with_extension("golden")).unwrap();
+
+            if let Some(diff) = diff_lines(&golden, &replay(&steps)) {
+                panic!("{} differs at {}", command_file.display(), diff);
+            }
+        }
+    }
+
+    #[test]
+    fn replay_format_test() {
+        let text = fs::read_to_string(golden_dir().join("conditions.cmd")).unwrap();
+        let steps = parse_steps(&text).unwrap();
+
+        // Formatted steps parse back to the same steps
+        let formatted: Vec<String> = steps.iter().map(format_step).collect();
+        let reparsed = parse_steps(&formatted.join("\n")).unwrap();
+        assert_eq!(
+            reparsed.iter().map(format_step).collect::<Vec<_>>(),
+            formatted
+        );
+        assert_eq!(replay(&reparsed), replay(&steps));
+
+        let spec = "spec 5 10 20 1000 50 500 5000";
+        assert_eq!(format_step(&parse_steps(spec).unwrap()[0]), spec);
+
+        assert!(matches!(
+            parse_steps("time 1\ninsert 1 buy limit 100"),
+            Err(ReplayError::Parse { line: 2, .. })
+        ));
+        assert!(matches!(
+            parse_steps("insert 1 buy limit 100 1 tif=NOW"),
+            Err(ReplayError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn replay_journal_test() {
+        let text = fs::read_to_string(golden_dir().join("matching.cmd")).unwrap();
+        let steps = parse_steps(&text).unwrap();
+
+        // Record the commands into a journal, as the book would on the live path
+        let mut journaled = JournaledBook::new(
+            OrderBook::new(100),
+            JournalWriter::new(Vec::new()),
+            ManualClock::new(1000),
+        );
+        for step in &steps {
+            if let ReplayStep::Command(command) = step {
+                _ = journaled.apply(command);
+            }
+        }
+        let (_, writer, _) = journaled.into_parts();
+
+        let journal_steps = journal_steps(writer.sink()).unwrap();
+        let journal_output = replay(&journal_steps);
+        let output = replay(&steps);
+
+        // Same events, the journal only adds the time of every command
+        let without_time = |output: &str| {
+            output
+                .lines()
+                .filter(|line| !line.starts_with("time "))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(without_time(&journal_output), without_time(&output));
+    }
+}
@@ -0,0 +1,277 @@
+#[cfg(test)]
+mod tests {
+    use market_forge::core::{
+        order::{ExecutionCondition, Order, OrderSide, SelfTradePrevention, TimeInForce},
+        order_book::OrderBook,
+        order_event::{CancelReason, OrderEvent},
+        order_match::OrderMatch,
+        order_spec::OrderSpec,
+    };
+
+    fn owned(
+        id: u32,
+        owner_id: u32,
+        order_side: OrderSide,
+        price: u64,
+        quantity: u64,
+    ) -> OrderSpec {
+        OrderSpec::limit_price(id, order_side, price, quantity).with_owner_id(owner_id)
+    }
+
+    fn book_with_resting_orders() -> OrderBook<OrderSpec> {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&owned(1, 7, OrderSide::Sell, 100, 5));
+        _ = book.insert_order(&owned(2, 8, OrderSide::Sell, 100, 5));
+
+        book
+    }
+
+    // Events of the prevented interactions, without the order flow of the last operation
+    fn prevention_events(book: &OrderBook<OrderSpec>) -> Vec<OrderEvent> {
+        book.events()
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    OrderEvent::SelfTradePrevented { .. }
+                        | OrderEvent::Cancelled { .. }
+                        | OrderEvent::Reduced { .. }
+                )
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn cancelled(order_id: u32, quantity: u64) -> OrderEvent {
+        OrderEvent::Cancelled {
+            order_id,
+            quantity,
+            reason: CancelReason::SelfTradePrevention,
+        }
+    }
+
+    fn prevented(order_id: u32, resting_order_id: u32) -> OrderEvent {
+        OrderEvent::SelfTradePrevented {
+            order_id,
+            resting_order_id,
+        }
+    }
+
+    #[test]
+    fn self_trade_allowed_test() {
+        let mut book = book_with_resting_orders();
+
+        // Self-trade prevention is opt-in
+        let res = book
+            .insert_order(&owned(3, 7, OrderSide::Buy, 100, 5))
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].match_to_id, 1);
+        assert!(prevention_events(&book).is_empty());
+    }
+
+    #[test]
+    fn self_trade_cancel_newest_test() {
+        let mut book = book_with_resting_orders();
+
+        let res = book.insert_order(
+            &owned(3, 7, OrderSide::Buy, 100, 8)
+                .with_self_trade_prevention(SelfTradePrevention::CancelNewest),
+        );
+        assert!(res.unwrap().is_empty());
+        assert_eq!(
+            prevention_events(&book),
+            vec![prevented(3, 1), cancelled(3, 8)]
+        );
+
+        // Incoming order is cancelled, resting orders are untouched
+        assert_eq!(book.asks().total_quantity(), 10);
+        assert_eq!(book.bids().len(), 0);
+        assert_eq!(book.order_allocator().len(), 2);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn self_trade_cancel_oldest_test() {
+        let mut book = book_with_resting_orders();
+
+        let res = book.insert_order(
+            &owned(3, 7, OrderSide::Buy, 100, 8)
+                .with_self_trade_prevention(SelfTradePrevention::CancelOldest),
+        );
+        assert_eq!(
+            res,
+            Ok(vec![OrderMatch {
+                order_side: OrderSide::Buy,
+                price: 100,
+                quantity: 5,
+                match_from_id: 3,
+                match_to_id: 2,
+                timestamp: 0,
+            }])
+        );
+        assert_eq!(
+            prevention_events(&book),
+            vec![prevented(3, 1), cancelled(1, 5)]
+        );
+
+        // Resting order is cancelled, incoming order keeps matching
+        assert_eq!(book.asks().len(), 0);
+        assert_eq!(book.bids().total_quantity(), 3);
+        assert_eq!(book.order_allocator().len(), 1);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn self_trade_cancel_both_test() {
+        let mut book = book_with_resting_orders();
+
+        let res = book.insert_order(
+            &owned(3, 7, OrderSide::Buy, 100, 8)
+                .with_self_trade_prevention(SelfTradePrevention::CancelBoth),
+        );
+        assert!(res.unwrap().is_empty());
+        assert_eq!(
+            prevention_events(&book),
+            vec![prevented(3, 1), cancelled(1, 5), cancelled(3, 8)]
+        );
+
+        assert_eq!(book.asks().total_quantity(), 5);
+        assert_eq!(book.bids().len(), 0);
+        assert_eq!(book.order_allocator().len(), 1);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn self_trade_decrement_and_cancel_test() {
+        let mut book = book_with_resting_orders();
+
+        // Smaller resting order is cancelled, incoming order is reduced and keeps matching
+        let res = book
+            .insert_order(
+                &owned(3, 7, OrderSide::Buy, 100, 8)
+                    .with_self_trade_prevention(SelfTradePrevention::DecrementAndCancel),
+            )
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].quantity, 3);
+        assert_eq!(
+            prevention_events(&book),
+            vec![
+                prevented(3, 1),
+                cancelled(1, 5),
+                OrderEvent::Reduced {
+                    order_id: 3,
+                    quantity: 3,
+                },
+            ]
+        );
+        assert_eq!(book.asks().total_quantity(), 2);
+        assert_eq!(book.bids().len(), 0);
+
+        // Smaller incoming order is cancelled, resting order is reduced
+        let res = book.insert_order(
+            &owned(4, 8, OrderSide::Buy, 100, 1)
+                .with_self_trade_prevention(SelfTradePrevention::DecrementAndCancel),
+        );
+        assert!(res.unwrap().is_empty());
+        assert_eq!(
+            prevention_events(&book),
+            vec![
+                prevented(4, 2),
+                OrderEvent::Reduced {
+                    order_id: 2,
+                    quantity: 1,
+                },
+                cancelled(4, 1),
+            ]
+        );
+        assert_eq!(book.asks().total_quantity(), 1);
+        assert_eq!(book.order_allocator().len(), 1);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn self_trade_fill_or_kill_test() {
+        let mut book = book_with_resting_orders();
+
+        // Own resting order is no liquidity, the order is killed before any fill
+        let res = book.insert_order(
+            &owned(3, 7, OrderSide::Buy, 100, 10)
+                .with_time_in_force(TimeInForce::FOK)
+                .with_self_trade_prevention(SelfTradePrevention::CancelOldest),
+        );
+        assert!(res.unwrap().is_empty());
+        assert_eq!(
+            prevention_events(&book),
+            vec![OrderEvent::Cancelled {
+                order_id: 3,
+                quantity: 10,
+                reason: CancelReason::FillOrKill,
+            }]
+        );
+        assert_eq!(book.asks().total_quantity(), 10);
+
+        // Incoming order would be cancelled at the own resting order
+        let res = book.insert_order(
+            &owned(4, 7, OrderSide::Buy, 100, 5)
+                .with_time_in_force(TimeInForce::FOK)
+                .with_self_trade_prevention(SelfTradePrevention::CancelNewest),
+        );
+        assert!(res.unwrap().is_empty());
+        assert_eq!(book.asks().total_quantity(), 10);
+
+        // Enough liquidity from other owners
+        let res = book
+            .insert_order(
+                &owned(5, 7, OrderSide::Buy, 100, 5)
+                    .with_time_in_force(TimeInForce::FOK)
+                    .with_self_trade_prevention(SelfTradePrevention::CancelOldest),
+            )
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].match_to_id, 2);
+        assert_eq!(
+            prevention_events(&book),
+            vec![prevented(5, 1), cancelled(1, 5)]
+        );
+        assert_eq!(book.order_allocator().len(), 0);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn self_trade_all_or_none_test() {
+        let mut book = book_with_resting_orders();
+
+        // Not fillable without the own resting order, rests untouched
+        let res = book.insert_order(
+            &owned(3, 7, OrderSide::Buy, 100, 10)
+                .with_execution_condition(ExecutionCondition::AON)
+                .with_self_trade_prevention(SelfTradePrevention::CancelOldest),
+        );
+        assert!(res.unwrap().is_empty());
+        assert!(prevention_events(&book).is_empty());
+        assert_eq!(book.asks().total_quantity(), 10);
+        assert_eq!(book.bids().total_quantity(), 10);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use market_forge::core::{
+        order::{
+            ExecutionCondition, MarketProtection, Order, OrderSide, OrderType, TimeInForce,
+            TrailingOffset,
+        },
+        order_match::OrderMatch,
+        order_spec::OrderSpec,
+    };
+
+    #[test]
+    fn serde_order_spec_test() {
+        let order = OrderSpec::limit_price(1, OrderSide::Buy, 100, 5)
+            .with_time_in_force(TimeInForce::GTT)
+            .with_expire_time(5_000)
+            .with_execution_condition(ExecutionCondition::AON)
+            .with_market_protection(MarketProtection::Ticks(2))
+            .with_owner_id(7);
+
+        let json = serde_json::to_string(&order).unwrap();
+        let decoded: OrderSpec = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+        assert_eq!(decoded.order_side, OrderSide::Buy);
+        assert_eq!(decoded.time_in_force, TimeInForce::GTT);
+        assert_eq!(decoded.expire_time, 5_000);
+
+        let trailing =
+            OrderSpec::trailing_stop(2, OrderSide::Sell, TrailingOffset::BasisPoints(50), 3);
+        let decoded: OrderSpec =
+            serde_json::from_str(&serde_json::to_string(&trailing).unwrap()).unwrap();
+        assert_eq!(decoded.order_type, OrderType::TrailingStop);
+        assert_eq!(
+            decoded.trailing_offset,
+            Some(TrailingOffset::BasisPoints(50))
+        );
+    }
+
+    #[test]
+    fn serde_order_match_test() {
+        let order_match = OrderMatch {
+            order_side: OrderSide::Sell,
+            price: 101,
+            quantity: 4,
+            match_from_id: 2,
+            match_to_id: 1,
+            timestamp: 1_000,
+        };
+
+        let json = serde_json::to_string(&order_match).unwrap();
+        assert_eq!(
+            json,
+            r#"{"order_side":"Sell","price":101,"quantity":4,"match_from_id":2,"match_to_id":1,"timestamp":1000}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<OrderMatch>(&json).unwrap(),
+            order_match
+        );
+        assert_eq!(
+            serde_json::from_str::<ExecutionCondition>(r#""AON""#).unwrap(),
+            ExecutionCondition::AON
+        );
+    }
+}
@@ -0,0 +1,229 @@
+#[cfg(test)]
+mod tests {
+    use fake::{
+        Rng,
+        rand::{SeedableRng, rngs::StdRng},
+    };
+    use market_forge::core::{
+        order::{
+            ExecutionCondition, Order, OrderId, OrderSide, Price, Quantity, TimeInForce,
+            TrailingOffset,
+        },
+        order_book::OrderBook,
+        order_error::OrderError,
+        order_link::OcoMode,
+        order_match::OrderMatch,
+        order_snapshot::{SNAPSHOT_VERSION, SnapshotError},
+        order_spec::OrderSpec,
+    };
+    use market_forge::{
+        codec::{Encoder, OrderCodec},
+        utils::crc32,
+    };
+
+    // Apply a random command, the same seed gives the same commands
+    fn random_command(
+        book: &mut OrderBook<OrderSpec>,
+        rng: &mut StdRng,
+        order_id: OrderId,
+    ) -> Vec<OrderMatch> {
+        let order_side = if rng.random_bool(0.5) {
+            OrderSide::Buy
+        } else {
+            OrderSide::Sell
+        };
+        let price = rng.random_range(90..110) as Price;
+        let quantity = rng.random_range(1..10) as Quantity;
+
+        match rng.random_range(0..12) {
+            0 => book
+                .insert_order(&OrderSpec::market(order_id, order_side, quantity))
+                .unwrap_or_default(),
+            1 => book
+                .insert_order(&OrderSpec::stop_limit(
+                    order_id, order_side, price, price, quantity,
+                ))
+                .unwrap_or_default(),
+            2 => book
+                .insert_order(&OrderSpec::trailing_stop(
+                    order_id,
+                    order_side,
+                    TrailingOffset::Absolute(3),
+                    quantity,
+                ))
+                .unwrap_or_default(),
+            3 => book
+                .insert_order(
+                    &OrderSpec::limit_price(order_id, order_side, price, quantity)
+                        .with_execution_condition(ExecutionCondition::AON),
+                )
+                .unwrap_or_default(),
+            4 => book
+                .insert_oco_order(
+                    &OrderSpec::limit_price(order_id, order_side, price, quantity),
+                    &OrderSpec::stop_market(
+                        order_id + 100_000,
+                        order_side.opposite(),
+                        price,
+                        quantity,
+                    ),
+                    OcoMode::Reduce,
+                )
+                .unwrap_or_default(),
+            5 => book
+                .insert_order(
+                    &OrderSpec::limit_price(order_id, order_side, price, quantity)
+                        .with_time_in_force(TimeInForce::GTT)
+                        .with_expire_time(order_id as u64 + 50),
+                )
+                .unwrap_or_default(),
+            6 => {
+                _ = book.advance_time(order_id as u64);
+                Vec::new()
+            }
+            7 | 8 => {
+                _ = book.cancel_order_by_id(rng.random_range(0..order_id + 1));
+                Vec::new()
+            }
+            _ => book
+                .insert_order(&OrderSpec::limit_price(
+                    order_id, order_side, price, quantity,
+                ))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn resting_orders(book: &OrderBook<OrderSpec>) -> Vec<(Price, usize, OrderId, Quantity)> {
+        book.bid_orders()
+            .chain(book.ask_orders())
+            .map(|item| {
+                (
+                    item.price,
+                    item.queue_position,
+                    item.order.id(),
+                    item.order.quantity(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn snapshot_restore_test() {
+        let mut book = OrderBook::<OrderSpec>::new(10_000);
+        let mut rng = StdRng::seed_from_u64(22);
+        for i in 0..3_000 {
+            random_command(&mut book, &mut rng, i);
+        }
+
+        let snapshot = book.snapshot(1);
+        let mut restored = OrderBook::<OrderSpec>::new(10_000);
+        restored.set_depth_cache(5);
+        restored.restore(&snapshot).unwrap();
+
+        assert_eq!(resting_orders(&restored), resting_orders(&book));
+        assert_eq!(restored.current_market_price(), book.current_market_price());
+        assert_eq!(restored.current_time(), book.current_time());
+        assert_eq!(restored.depth_cache().unwrap(), &book.depth(5));
+        assert_eq!(restored.snapshot(1), snapshot);
+
+        // Both books behave the same from here on
+        let mut rng = StdRng::seed_from_u64(23);
+        let mut restored_rng = rng.clone();
+        for i in 3_000..6_000 {
+            assert_eq!(
+                random_command(&mut restored, &mut restored_rng, i),
+                random_command(&mut book, &mut rng, i),
+            );
+            assert!(restored.validate_cache().is_ok());
+        }
+        assert_eq!(restored.snapshot(1), book.snapshot(1));
+    }
+
+    #[test]
+    fn snapshot_restore_replaces_book_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Buy, 99, 2));
+        _ = book.insert_order(&OrderSpec::stop_market(2, OrderSide::Sell, 95, 3));
+        let snapshot = book.snapshot(1);
+
+        let mut restored = OrderBook::<OrderSpec>::new(100);
+        _ = restored.insert_order(&OrderSpec::limit_price(3, OrderSide::Sell, 101, 4));
+        restored.restore(&snapshot).unwrap();
+
+        assert!(restored.get_order(3).is_none());
+        assert_eq!(restored.get_order(1).unwrap().quantity(), 2);
+        assert_eq!(restored.get_order(2).unwrap().stop_price(), 95);
+        assert_eq!(restored.order_allocator().len(), 2);
+    }
+
+    #[test]
+    fn snapshot_invalid_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Buy, 99, 2));
+        let snapshot = book.snapshot(1);
+        let mut restored = OrderBook::<OrderSpec>::new(100);
+
+        let mut bytes = snapshot.clone();
+        bytes[0] = b'X';
+        assert_eq!(restored.restore(&bytes), Err(SnapshotError::InvalidMagic));
+
+        let mut bytes = snapshot.clone();
+        bytes[4..6].copy_from_slice(&(SNAPSHOT_VERSION + 1).to_le_bytes());
+        assert_eq!(
+            restored.restore(&bytes),
+            Err(SnapshotError::UnsupportedVersion(SNAPSHOT_VERSION + 1))
+        );
+
+        let mut bytes = snapshot.clone();
+        let last = bytes.len() - 10;
+        bytes[last] ^= 0xff;
+        assert_eq!(
+            restored.restore(&bytes),
+            Err(SnapshotError::ChecksumMismatch)
+        );
+
+        // Failed restore leaves the book alone
+        _ = restored.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 101, 1));
+        assert!(restored.restore(&snapshot[..snapshot.len() - 1]).is_err());
+        assert!(restored.get_order(2).is_some());
+    }
+
+    #[test]
+    fn snapshot_duplicate_order_test() {
+        let ask = OrderSpec::limit_price(2, OrderSide::Sell, 101, 4);
+        let mut book = OrderBook::<OrderSpec>::new(100);
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Buy, 99, 2));
+        _ = book.insert_order(&ask);
+        let mut snapshot = book.snapshot(1);
+
+        // Give the ask the id of the bid, with a valid checksum
+        let encode = |order: &OrderSpec| {
+            let mut encoder = Encoder::new();
+            order.encode(&mut encoder);
+            encoder.into_bytes()
+        };
+        let resting_ask = book.get_order(2).unwrap().clone();
+        let mut duplicate = resting_ask.clone();
+        duplicate.id = 1;
+        let (from, to) = (encode(&resting_ask), encode(&duplicate));
+        let start = snapshot
+            .windows(from.len())
+            .position(|window| window == from.as_slice())
+            .unwrap();
+        snapshot[start..start + to.len()].copy_from_slice(&to);
+        let body_len = snapshot.len() - 4;
+        let checksum = crc32(&snapshot[..body_len]);
+        snapshot[body_len..].copy_from_slice(&checksum.to_le_bytes());
+
+        // Rejected before the book is cleared
+        let mut restored = OrderBook::<OrderSpec>::new(100);
+        _ = restored.insert_order(&OrderSpec::limit_price(3, OrderSide::Sell, 102, 1));
+        assert_eq!(
+            restored.restore(&snapshot),
+            Err(SnapshotError::Order(OrderError::DuplicateOrderId))
+        );
+        assert!(restored.get_order(3).is_some());
+        assert_eq!(restored.order_allocator().len(), 1);
+        assert_eq!(restored.asks().total_quantity(), 1);
+    }
+}
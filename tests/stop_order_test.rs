@@ -0,0 +1,289 @@
+#[cfg(test)]
+mod tests {
+    use market_forge::core::{
+        order::{Order, OrderSide, OrderType, TrailingOffset},
+        order_book::OrderBook,
+        order_error::OrderError,
+        order_match::OrderMatch,
+        order_replace::ReplaceOutcome,
+        order_spec::OrderSpec,
+        trailing_stops::TrailingStops,
+    };
+
+    #[test]
+    fn stop_market_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 120, 5));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 121, 5));
+
+        // No trade yet, stop order should be parked
+        let res = book.insert_order(&OrderSpec::stop_market(3, OrderSide::Buy, 120, 3));
+        assert!(
+            res.unwrap().is_empty(),
+            "stop order should not match before trigger"
+        );
+        assert_eq!(book.stop_bids().total_quantity(), 3);
+        assert_eq!(book.asks().total_quantity(), 10);
+
+        // Trade at 120 hit the stop price
+        let res = book.insert_order(&OrderSpec::limit_price(4, OrderSide::Buy, 120, 1));
+        assert_eq!(
+            res,
+            Ok(vec![
+                OrderMatch {
+                    order_side: OrderSide::Buy,
+                    price: 120,
+                    quantity: 1,
+                    match_from_id: 4,
+                    match_to_id: 1,
+                    timestamp: 0,
+                },
+                OrderMatch {
+                    order_side: OrderSide::Buy,
+                    price: 120,
+                    quantity: 3,
+                    match_from_id: 3,
+                    match_to_id: 1,
+                    timestamp: 0,
+                },
+            ]),
+            "stop market should be triggered after trade"
+        );
+
+        assert_eq!(book.stop_bids().len(), 0, "stop order should be released");
+        assert_eq!(book.asks().total_quantity(), 6);
+        assert_eq!(book.order_allocator().len(), 2);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn stop_limit_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Buy, 100, 5));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 99, 5));
+
+        // Sell stop limit, trigger at 100 and sell at 101
+        _ = book.insert_order(&OrderSpec::stop_limit(3, OrderSide::Sell, 100, 101, 4));
+        assert_eq!(book.stop_asks().total_quantity(), 4);
+
+        let res = book.insert_order(&OrderSpec::market(4, OrderSide::Sell, 2));
+        assert_eq!(res.unwrap().len(), 1, "stop limit should not match");
+
+        // Stop limit should rest as limit order at 101
+        assert_eq!(book.stop_asks().len(), 0, "stop order should be released");
+        assert_eq!(book.asks().peek_key(), Some(&101));
+        assert_eq!(book.asks().total_quantity(), 4);
+
+        let order = book.order_allocator().iter().find(|(_, o)| o.id == 3);
+        assert_eq!(order.unwrap().1.order_type, OrderType::Limit);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn stop_cascade_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Buy, 100, 2));
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Buy, 98, 2));
+        _ = book.insert_order(&OrderSpec::limit_price(3, OrderSide::Buy, 96, 2));
+
+        // First stop is hit by the trade at 100 and trades at 98,
+        // which hits the second stop trading at 96
+        _ = book.insert_order(&OrderSpec::stop_market(4, OrderSide::Sell, 100, 2));
+        _ = book.insert_order(&OrderSpec::stop_market(5, OrderSide::Sell, 98, 2));
+        _ = book.insert_order(&OrderSpec::stop_market(6, OrderSide::Sell, 90, 2));
+
+        let res = book
+            .insert_order(&OrderSpec::market(7, OrderSide::Sell, 2))
+            .unwrap();
+        let prices: Vec<_> = res.iter().map(|m| (m.match_from_id, m.price)).collect();
+        assert_eq!(prices, vec![(7, 100), (4, 98), (5, 96)]);
+
+        assert_eq!(book.current_market_price(), 96);
+        assert_eq!(book.bids().len(), 0);
+        assert_eq!(book.stop_asks().len(), 1, "stop at 90 should not be hit");
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn stop_triggered_on_arrival_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Sell, 110, 10));
+        _ = book.insert_order(&OrderSpec::market(2, OrderSide::Buy, 1));
+        assert_eq!(book.current_market_price(), 110);
+
+        // Market already above the stop price
+        let res = book.insert_order(&OrderSpec::stop_market(3, OrderSide::Buy, 105, 4));
+        assert_eq!(res.unwrap()[0].quantity, 4);
+        assert_eq!(book.stop_bids().len(), 0);
+        assert_eq!(book.asks().total_quantity(), 5);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn trailing_stop_sell_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Buy, 100, 10));
+        _ = book.insert_order(&OrderSpec::market(2, OrderSide::Sell, 1));
+
+        // Stop price trails 5 below the market price
+        _ = book.insert_order(&OrderSpec::trailing_stop(
+            3,
+            OrderSide::Sell,
+            TrailingOffset::Absolute(5),
+            4,
+        ));
+        assert_eq!(book.stop_asks().peek_key(), Some(&95));
+
+        // Rising market moves the stop price up
+        _ = book.insert_order(&OrderSpec::limit_price(4, OrderSide::Sell, 110, 1));
+        _ = book.insert_order(&OrderSpec::market(5, OrderSide::Buy, 1));
+        assert_eq!(book.current_market_price(), 110);
+        assert_eq!(book.stop_asks().peek_key(), Some(&105));
+        assert_eq!(book.stop_asks().total_quantity(), 4);
+
+        // Falling market never moves the stop price down
+        _ = book.insert_order(&OrderSpec::limit_price(6, OrderSide::Sell, 106, 1));
+        _ = book.insert_order(&OrderSpec::market(7, OrderSide::Buy, 1));
+        assert_eq!(book.stop_asks().peek_key(), Some(&105));
+
+        // Trade at 100 hit the stop price, sell at market
+        let res = book
+            .insert_order(&OrderSpec::market(8, OrderSide::Sell, 1))
+            .unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[1].match_from_id, 3);
+        assert_eq!(res[1].quantity, 4);
+        assert_eq!(book.stop_asks().len(), 0);
+        assert_eq!(book.bids().total_quantity(), 4);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn trailing_stop_buy_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        // Not armed until the first trade
+        _ = book.insert_order(&OrderSpec::trailing_stop(
+            1,
+            OrderSide::Buy,
+            TrailingOffset::BasisPoints(500),
+            2,
+        ));
+
+        _ = book.insert_order(&OrderSpec::limit_price(2, OrderSide::Sell, 200, 10));
+        _ = book.insert_order(&OrderSpec::market(3, OrderSide::Buy, 1));
+        assert_eq!(book.stop_bids().peek_key().map(|p| p.0), Some(210));
+
+        // Falling market moves the stop price down
+        _ = book.insert_order(&OrderSpec::limit_price(4, OrderSide::Buy, 180, 1));
+        _ = book.insert_order(&OrderSpec::market(5, OrderSide::Sell, 1));
+        assert_eq!(book.stop_bids().peek_key().map(|p| p.0), Some(189));
+
+        // Trade at 200 hit the stop price
+        let res = book
+            .insert_order(&OrderSpec::market(6, OrderSide::Buy, 1))
+            .unwrap();
+        assert_eq!(res[1].match_from_id, 1);
+        assert_eq!(res[1].quantity, 2);
+        assert_eq!(book.stop_bids().len(), 0);
+        assert_eq!(book.asks().total_quantity(), 6);
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+
+    #[test]
+    fn trailing_stops_pop_moving_test() {
+        let mut trailing_stops = TrailingStops::new();
+        trailing_stops.insert(1, OrderSide::Sell, 100);
+        trailing_stops.insert(2, OrderSide::Sell, 110);
+        trailing_stops.insert(3, OrderSide::Buy, 100);
+        trailing_stops.insert(4, OrderSide::Buy, 90);
+
+        // Only the orders trailed from below the rising market
+        assert_eq!(trailing_stops.pop_moving(105), vec![1]);
+        assert_eq!(trailing_stops.pop_moving(105), Vec::<u32>::new());
+        assert_eq!(trailing_stops.pop_moving(120), vec![1, 2]);
+
+        // Only the orders trailed from above the falling market
+        assert_eq!(trailing_stops.pop_moving(95), vec![3]);
+        assert_eq!(trailing_stops.pop_moving(95), Vec::<u32>::new());
+        assert_eq!(trailing_stops.pop_moving(80), vec![3, 4]);
+
+        trailing_stops.remove(3);
+        assert_eq!(trailing_stops.len(), 3);
+        assert_eq!(trailing_stops.pop_moving(70), vec![4]);
+    }
+
+    #[test]
+    fn stop_replace_test() {
+        let mut book = OrderBook::<OrderSpec>::new(100);
+
+        _ = book.insert_order(&OrderSpec::limit_price(1, OrderSide::Buy, 100, 10));
+        _ = book.insert_order(&OrderSpec::market(2, OrderSide::Sell, 1));
+        _ = book.insert_order(&OrderSpec::stop_market(3, OrderSide::Sell, 90, 3));
+        _ = book.insert_order(&OrderSpec::stop_limit(4, OrderSide::Buy, 110, 111, 2));
+
+        // New price is the stop price
+        let res = book.replace_order_by_id(3, 0, 95);
+        assert_eq!(res, Ok(ReplaceOutcome::Requeued { matches: vec![] }));
+        assert_eq!(book.get_order(3).unwrap().stop_price(), 95);
+        assert_eq!(book.stop_asks().peek_key(), Some(&95));
+
+        // Stop limit order keeps its limit price
+        assert!(book.replace_order_by_id(4, 1, 105).is_ok());
+        let order = book.get_order(4).unwrap();
+        assert_eq!(
+            (order.stop_price(), order.price(), order.quantity()),
+            (105, 111, 3)
+        );
+        assert_eq!(book.stop_bids().total_quantity(), 3);
+
+        // Trailing stop order takes its stop price from the market
+        _ = book.insert_order(&OrderSpec::trailing_stop(
+            5,
+            OrderSide::Sell,
+            TrailingOffset::Absolute(5),
+            4,
+        ));
+        assert_eq!(
+            book.replace_order_by_id(5, 0, 90),
+            Err(OrderError::TrailingStopPriceReplace)
+        );
+        assert_eq!(book.get_order(5).unwrap().stop_price(), 95);
+
+        // Stop price already hit triggers the order right away
+        let res = book.replace_order_by_id(3, 0, 100);
+        let Ok(ReplaceOutcome::Requeued { matches }) = res else {
+            panic!("expected requeued order");
+        };
+        assert_eq!(matches.len(), 1);
+        assert_eq!(book.bids().total_quantity(), 6);
+        assert!(book.get_order(3).is_none());
+
+        if let Some(err) = book.validate_cache().err() {
+            panic!("{:?}", err);
+        }
+    }
+}